@@ -3,7 +3,25 @@
 //! component  to be used in our app.
 
 mod timer;
-pub use timer::Timer;
+pub use timer::{
+    new_bank, restore_bank, use_bank, Adjustments, Advance, BankSnapshot, Budget, Countdown, Cycle,
+    Format, History, Level, Limit, Mode, Overtime, PlayerEvent, Rounding, Rules, Segment, Sequence,
+    Sign, Stats, Thresholds, TimeControl, Timer, TimerBank, TimerEvent, MAX_DURATION,
+};
 
 mod table;
-pub use table::Table;
+pub use table::{
+    close_agenda, parse_markdown, plan_agenda, sync_agenda, to_markdown, Agenda, Table, TableItem,
+};
+
+mod players;
+pub use players::{Players, Roster};
+
+mod shortcuts;
+pub use shortcuts::{toggle_fullscreen, use_shortcuts, Shortcuts};
+
+mod segments;
+pub use segments::SegmentStrip;
+
+mod stats;
+pub use stats::StatsPanel;
@@ -0,0 +1,219 @@
+use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Player {
+    id: usize,
+    name: String,
+}
+
+impl Player {
+    fn new(id: usize, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// The ordered list of players taking turns, shared between the lobby and the
+/// play view and carried in the [`crate::config::Config`] of a link.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Roster(Vec<Player>);
+
+impl Roster {
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Name of the player at `index`, falling back to a numbered placeholder.
+    pub fn name(&self, index: usize) -> String {
+        match self.0.get(index) {
+            Some(player) => player.name.clone(),
+            None => format!("Player {}", index + 1),
+        }
+    }
+
+    fn next_id(&self) -> usize {
+        self.0.iter().map(|p| p.id + 1).max().unwrap_or(0)
+    }
+
+    pub fn add(&mut self, name: &str) -> bool {
+        let mut player = Player::new(self.next_id(), name);
+        player.name = player.name.trim().to_string();
+        if player.name.is_empty() {
+            return false;
+        }
+        self.0.push(player);
+        true
+    }
+
+    pub fn rename(&mut self, id: usize, name: &str) {
+        if let Some(player) = self.0.iter_mut().find(|p| p.id == id) {
+            *player = Player::new(id, name);
+        }
+    }
+
+    /// Tidies up a name after editing, dropping the player if it was cleared.
+    pub fn finish_edit(&mut self, id: usize) {
+        if let Some(player) = self.0.iter_mut().find(|p| p.id == id) {
+            player.name = player.name.trim().to_string();
+        }
+        self.0.retain(|p| p.id != id || !p.name.is_empty());
+    }
+
+    pub fn remove(&mut self, id: usize) {
+        self.0.retain(|p| p.id != id);
+    }
+
+    pub fn reorder(&mut self, from: usize, to: usize) {
+        if from != to && from < self.0.len() && to < self.0.len() {
+            let player = self.0.remove(from);
+            self.0.insert(to, player);
+        }
+    }
+}
+
+#[component]
+pub fn Players() -> Element {
+    let mut roster = use_context::<Signal<Roster>>();
+    let mut editing_id = use_signal(|| Option::<usize>::None);
+    let mut drag_source = use_signal(|| Option::<usize>::None);
+    let mut new_player_name = use_signal(String::new);
+
+    rsx! {
+        div { class: "flex flex-col space-y-2 w-full",
+            label { class: "text-gray-300 font-semibold", "Players" }
+            div { class: "w-full border border-gray-600 rounded p-2 bg-gray-700",
+                for (index , player) in roster.read().clone().0.into_iter().enumerate() {
+                    div {
+                        key: "{player.id}",
+                        class: "flex items-center space-x-2 py-1 border-b border-gray-600 last:border-b-0 group",
+                        draggable: "true",
+                        ondragstart: move |_| drag_source.set(Some(index)),
+                        ondragover: move |e| e.prevent_default(),
+                        ondrop: move |e| {
+                            e.prevent_default();
+                            if let Some(src) = *drag_source.read() {
+                                roster.write().reorder(src, index);
+                            }
+                            drag_source.set(None);
+                        },
+                        div { class: "cursor-move text-slate-400 hover:text-white px-2",
+                            "\u{2630}"
+                        }
+                        if Some(player.id) == *editing_id.read() {
+                            input {
+                                class: "flex-grow bg-slate-800 text-white p-1 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                                r#type: "text",
+                                value: "{player.name}",
+                                autofocus: true,
+                                onkeydown: move |e| {
+                                    if matches!(e.key(), Key::Enter | Key::Escape) {
+                                        roster.write().finish_edit(player.id);
+                                        editing_id.set(None);
+                                    }
+                                },
+                                oninput: move |e| roster.write().rename(player.id, &e.value()),
+                                onblur: move |_| {
+                                    roster.write().finish_edit(player.id);
+                                    editing_id.set(None);
+                                },
+                            }
+                        } else {
+                            span {
+                                class: "flex-grow cursor-pointer text-white",
+                                onclick: move |_| editing_id.set(Some(player.id)),
+                                "{player.name}"
+                            }
+                        }
+                        button {
+                            class: "text-red-500 hover:text-red-400 font-bold px-2 invisible group-hover:visible",
+                            onclick: move |_| roster.write().remove(player.id),
+                            "✕"
+                        }
+                    }
+                }
+                input {
+                    class: "w-full bg-slate-800 text-white p-2 mt-1 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                    r#type: "text",
+                    placeholder: "Add player...",
+                    value: "{new_player_name}",
+                    oninput: move |e| new_player_name.set(e.value()),
+                    onkeydown: move |e| {
+                        if e.key() == Key::Enter {
+                            let name = new_player_name.read().clone();
+                            if roster.write().add(&name) {
+                                new_player_name.set(String::new());
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roster(names: &[&str]) -> Roster {
+        let mut roster = Roster::default();
+        for name in names {
+            roster.add(name);
+        }
+        roster
+    }
+
+    fn names(roster: &Roster) -> Vec<String> {
+        (0..roster.len()).map(|index| roster.name(index)).collect()
+    }
+
+    #[test]
+    fn test_roster_json() {
+        let roster = roster(&["Alice", "Bob", "Carol"]);
+        assert_eq!(roster.len(), 3);
+        assert_eq!(roster.name(1), "Bob");
+        let json = serde_json::to_string(&roster).unwrap();
+        assert_eq!(serde_json::from_str::<Roster>(&json).unwrap(), roster);
+        assert!(Roster::default().is_empty());
+    }
+
+    #[test]
+    fn test_roster_keeps_names_whole() {
+        let mut roster = Roster::default();
+        assert!(roster.add("  Tom & Jerry  "));
+        assert!(roster.add("Smith, J."));
+        assert!(!roster.add("   "));
+        assert_eq!(names(&roster), ["Tom & Jerry", "Smith, J."]);
+        assert_eq!(roster.name(2), "Player 3");
+    }
+
+    #[test]
+    fn test_roster_edit() {
+        let mut roster = roster(&["Alice", "Bob", "Carol"]);
+        roster.reorder(0, 2);
+        assert_eq!(names(&roster), ["Bob", "Carol", "Alice"]);
+
+        let bob = roster.0[0].id;
+        roster.rename(bob, " Bobby ");
+        roster.finish_edit(bob);
+        roster.remove(roster.0[1].id);
+        assert_eq!(names(&roster), ["Bobby", "Alice"]);
+
+        let alice = roster.0[1].id;
+        roster.rename(alice, "  ");
+        roster.finish_edit(alice);
+        assert_eq!(names(&roster), ["Bobby"]);
+
+        assert!(roster.add("Dave"));
+        assert_eq!(names(&roster), ["Bobby", "Dave"]);
+        assert_ne!(roster.0[0].id, roster.0[1].id);
+    }
+}
@@ -0,0 +1,53 @@
+use dioxus::prelude::*;
+
+use super::TimerBank;
+
+/// The segments of a sequence side by side, each as wide as its share of the
+/// round, filled in as they are timed.
+#[component]
+pub fn SegmentStrip(bank: Signal<TimerBank>) -> Element {
+    let current = bank.read();
+    let Some((sequence, step)) = current.sequence() else {
+        return rsx! {};
+    };
+    let count = sequence.segments.len() as u32;
+    let round = sequence.round(step).min(sequence.rounds.max(1));
+    let position = match current.segment() {
+        Some(_) => step % count.max(1),
+        None => count,
+    };
+    let progress = current.progress().unwrap_or_default().clamp(0.0, 1.0);
+    let fill = |index: u32| match index.cmp(&position) {
+        std::cmp::Ordering::Less => 1.0,
+        std::cmp::Ordering::Equal => progress,
+        std::cmp::Ordering::Greater => 0.0,
+    };
+
+    rsx! {
+        div { class: "flex flex-col w-full m-1 space-y-1",
+            if sequence.rounds > 1 {
+                p { class: "text-sm text-slate-400", "Round {round} of {sequence.rounds}" }
+            }
+            div { class: "flex gap-1 w-full",
+                for (index , segment) in sequence.segments.iter().enumerate() {
+                    div {
+                        key: "{index}",
+                        class: "flex flex-col min-w-0",
+                        style: "flex: {segment.duration.num_milliseconds().max(1)} 1 0%",
+                        div { class: "h-2 rounded bg-gray-800 overflow-hidden",
+                            div {
+                                class: if index as u32 == position { "h-full bg-blue-500" } else { "h-full bg-slate-500" },
+                                style: "width: {fill(index as u32) * 100f32}%",
+                            }
+                        }
+                        span {
+                            class: if index as u32 == position { "truncate text-xs text-white font-bold" } else { "truncate text-xs text-slate-400" },
+                            title: "{segment.name}",
+                            "{segment.name}"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use dioxus::prelude::*;
+
+use crate::keymap::{key_label, key_name, Action, Keymap};
+
+/// Forwards key presses from the whole page, except while a field has focus,
+/// and holds back the browser's own use of bound keys such as space to scroll.
+/// `name` matches [`key_name`].
+const LISTEN: &str = r#"
+const name = (key) => key === " " ? "Space" : [...key].length === 1 ? key.toLowerCase() : key;
+const editing = (target) =>
+    target.isContentEditable || ["INPUT", "TEXTAREA", "SELECT"].includes(target.tagName);
+let bound = [];
+if (window.momentShortcuts) document.removeEventListener("keydown", window.momentShortcuts);
+window.momentShortcuts = (e) => {
+    if (e.ctrlKey || e.metaKey || e.altKey || e.isComposing || editing(e.target)) return;
+    if (bound.includes(name(e.key))) e.preventDefault();
+    dioxus.send(e.key);
+};
+document.addEventListener("keydown", window.momentShortcuts);
+while (true) bound = await dioxus.recv();
+"#;
+
+const UNLISTEN: &str = r#"
+if (window.momentShortcuts) document.removeEventListener("keydown", window.momentShortcuts);
+window.momentShortcuts = null;
+"#;
+
+/// Calls `on_key` with the [`key_name`] of each key pressed on the page.
+pub fn use_shortcuts(keymap: Signal<Keymap>, on_key: impl FnMut(String) + 'static) {
+    let on_key = use_callback(on_key);
+    let mut listener = use_hook(|| document::eval(LISTEN));
+    use_effect(move || {
+        let _ = listener.send(keymap.read().bound());
+    });
+    use_future(move || async move {
+        while let Ok(key) = listener.recv::<String>().await {
+            on_key.call(key_name(&key));
+        }
+    });
+    use_drop(|| {
+        document::eval(UNLISTEN);
+    });
+}
+
+pub fn toggle_fullscreen() {
+    document::eval(
+        "if (document.fullscreenElement) document.exitFullscreen(); \
+         else document.documentElement.requestFullscreen();",
+    );
+}
+
+/// The help overlay, listing every shortcut with a button to rebind it. While
+/// `remapping` is set the next key pressed is bound to that action instead.
+#[component]
+pub fn Shortcuts(
+    keymap: Signal<Keymap>,
+    open: Signal<bool>,
+    remapping: Signal<Option<Action>>,
+) -> Element {
+    if !open() {
+        return rsx! {};
+    }
+
+    rsx! {
+        div {
+            class: "fixed inset-0 z-10 flex items-center justify-center bg-black/60",
+            onclick: move |_| {
+                remapping.set(None);
+                open.set(false);
+            },
+            div {
+                class: "w-full max-w-md p-6 rounded-lg bg-slate-800 border border-slate-700 shadow-xl",
+                onclick: move |e| e.stop_propagation(),
+                h2 { class: "text-xl font-bold mb-4", "Keyboard shortcuts" }
+                table { class: "w-full text-sm",
+                    tbody {
+                        for action in Action::ALL {
+                            tr {
+                                key: "{action:?}",
+                                class: "border-b border-slate-700 last:border-b-0",
+                                td { class: "py-2 text-slate-300", "{action.description()}" }
+                                td { class: "py-2 text-right",
+                                    if remapping() == Some(action) {
+                                        span { class: "text-blue-400", "Press a key\u{2026}" }
+                                    } else {
+                                        for key in keymap.read().keys(action) {
+                                            kbd {
+                                                key: "{key}",
+                                                class: "ml-1 px-2 py-0.5 rounded bg-slate-700 font-mono",
+                                                "{key_label(key)}"
+                                            }
+                                        }
+                                    }
+                                }
+                                td { class: "py-2 pl-2 text-right",
+                                    button {
+                                        class: "text-slate-400 hover:text-white",
+                                        onclick: move |_| remapping.set(Some(action)),
+                                        "Change"
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                div { class: "flex justify-between mt-4",
+                    button {
+                        class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                        onclick: move |_| {
+                            remapping.set(None);
+                            keymap.set(Keymap::default());
+                        },
+                        "Reset"
+                    }
+                    button {
+                        class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold",
+                        onclick: move |_| {
+                            remapping.set(None);
+                            open.set(false);
+                        },
+                        "Done"
+                    }
+                }
+            }
+        }
+    }
+}
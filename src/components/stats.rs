@@ -0,0 +1,60 @@
+use dioxus::prelude::*;
+
+use super::{Countdown, History, Roster, Stats};
+
+/// Collapsible summary of the turn times recorded so far.
+#[component]
+pub fn StatsPanel(history: History, players: Roster) -> Element {
+    let count = history
+        .laps()
+        .iter()
+        .map(|lap| lap.player + 1)
+        .max()
+        .unwrap_or(0);
+    let rows = (0..count.max(players.len()))
+        .filter_map(|index| Some((index, players.name(index), history.stats_for(index)?)));
+
+    rsx! {
+        details { class: "w-full m-1 rounded bg-gray-800 text-slate-300",
+            summary { class: "px-3 py-2 cursor-pointer font-bold", "Stats" }
+            if let Some(overall) = history.stats() {
+                table { class: "w-full text-sm text-right font-mono",
+                    thead {
+                        tr { class: "text-slate-400",
+                            th { class: "px-3 py-1 text-left font-sans", "" }
+                            th { class: "px-3 py-1", "turns" }
+                            th { class: "px-3 py-1", "min" }
+                            th { class: "px-3 py-1", "max" }
+                            th { class: "px-3 py-1", "mean" }
+                            th { class: "px-3 py-1", "median" }
+                            th { class: "px-3 py-1", "overrun" }
+                        }
+                    }
+                    tbody {
+                        for (index , name , stats) in rows {
+                            StatsRow { key: "{index}", label: name, stats }
+                        }
+                        StatsRow { key: "all", label: "All", stats: overall }
+                    }
+                }
+            } else {
+                p { class: "px-3 pb-2 text-sm text-slate-400", "No turns finished yet." }
+            }
+        }
+    }
+}
+
+#[component]
+fn StatsRow(label: String, stats: Stats) -> Element {
+    rsx! {
+        tr { class: "border-t border-gray-700",
+            td { class: "px-3 py-1 text-left font-sans", "{label}" }
+            td { class: "px-3 py-1", "{stats.turns}" }
+            td { class: "px-3 py-1", "{Countdown(stats.min)}" }
+            td { class: "px-3 py-1", "{Countdown(stats.max)}" }
+            td { class: "px-3 py-1", "{Countdown(stats.mean)}" }
+            td { class: "px-3 py-1", "{Countdown(stats.median)}" }
+            td { class: "px-3 py-1", "{Countdown(stats.overrun)}" }
+        }
+    }
+}
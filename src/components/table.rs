@@ -1,28 +1,285 @@
+use chrono::TimeDelta;
 use dioxus::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-struct TableItem {
-    id: usize,
-    description: String,
-    completed: bool,
+use super::{Countdown, Limit, Segment};
+use crate::templates::Templates;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TableItem {
+    pub id: usize,
+    pub description: String,
+    #[serde(default)]
+    pub completed: bool,
+    /// How long the item may take when the table is run as an agenda.
+    #[serde(default)]
+    pub timebox: Option<Limit>,
+    /// Where the item comes in the agenda being run, if it is part of it.
+    #[serde(default)]
+    pub step: Option<u32>,
+}
+
+/// How far the agenda has got: the step being timed, if any, and the time
+/// spent on every step so far, the current one included.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Agenda {
+    pub step: Option<u32>,
+    pub spent: Vec<TimeDelta>,
+}
+
+/// Lines up the open items with a timebox as the steps of a new agenda,
+/// returning the segments to time them by.
+pub fn plan_agenda(items: &mut [TableItem]) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    for item in items {
+        item.step = None;
+        if let (false, Some(timebox)) = (item.completed, item.timebox) {
+            item.step = Some(segments.len() as u32);
+            segments.push(Segment::new(&item.description, timebox.0));
+        }
+    }
+    segments
+}
+
+/// Ticks off the agenda items before `step` and reopens the rest, so that
+/// undoing a step reopens its item.
+pub fn sync_agenda(items: &mut [TableItem], step: u32) {
+    for item in items {
+        if let Some(at) = item.step {
+            item.completed = at < step;
+        }
+    }
+}
+
+/// Takes the items out of the agenda once the meeting is over, leaving them
+/// ticked off or not as they were.
+pub fn close_agenda(items: &mut [TableItem]) {
+    for item in items {
+        item.step = None;
+    }
+}
+
+/// Writes the items as a Markdown task list, with any timebox in brackets.
+/// A description that would read back as ending in a timebox has its last
+/// bracket escaped.
+pub fn to_markdown(items: &[TableItem]) -> String {
+    let mut markdown = String::new();
+    for item in items {
+        let mark = if item.completed { 'x' } else { ' ' };
+        let mut description = item.description.trim().to_string();
+        if split_timebox(&description).is_some() || description.ends_with("\\)") {
+            description.insert(description.len() - 1, '\\');
+        }
+        markdown.push_str(&format!("- [{mark}] {description}"));
+        if let Some(timebox) = item.timebox {
+            markdown.push_str(&format!(" ({timebox})"));
+        }
+        markdown.push('\n');
+    }
+    markdown
 }
 
+/// Reads the list items of a Markdown document as table items, numbering
+/// them from `first_id`. Plain list items count as open tasks; any other
+/// line, such as a heading, is skipped.
+pub fn parse_markdown(markdown: &str, first_id: usize) -> Vec<TableItem> {
+    let mut items = Vec::new();
+    for line in markdown.lines() {
+        let Some(rest) = ["- ", "* ", "+ "]
+            .iter()
+            .find_map(|bullet| line.trim().strip_prefix(bullet))
+        else {
+            continue;
+        };
+        let (completed, text) = match rest.get(..3) {
+            Some("[ ]") => (false, &rest[3..]),
+            Some("[x]" | "[X]") => (true, &rest[3..]),
+            _ => (false, rest),
+        };
+        let (description, timebox) = match split_timebox(text) {
+            Some((description, timebox)) => (description, Some(timebox)),
+            None => (text, None),
+        };
+        let description = description.trim();
+        if description.is_empty() {
+            continue;
+        }
+        let description = match description.strip_suffix("\\)") {
+            Some(head) => format!("{head})"),
+            None => description.to_string(),
+        };
+        items.push(TableItem {
+            id: first_id + items.len(),
+            description,
+            completed,
+            timebox,
+            step: None,
+        });
+    }
+    items
+}
+
+/// Splits a trailing timebox such as ` (2m)` off `text`.
+fn split_timebox(text: &str) -> Option<(&str, Limit)> {
+    let (description, timebox) = text.trim().strip_suffix(')')?.rsplit_once(" (")?;
+    Some((description, timebox.parse().ok()?))
+}
+
+/// Puts `markdown` on the clipboard.
+const COPY: &str = "navigator.clipboard.writeText(await dioxus.recv());";
+
+/// Saves `markdown` as a file through the browser's download.
+const DOWNLOAD: &str = r#"
+const text = await dioxus.recv();
+const link = document.createElement("a");
+link.href = URL.createObjectURL(new Blob([text], { type: "text/markdown" }));
+link.download = "table.md";
+link.click();
+// some browsers only start the download after the click returns
+setTimeout(() => URL.revokeObjectURL(link.href), 1000);
+"#;
+
 #[component]
-pub fn Table() -> Element {
-    let mut items = use_signal(Vec::<TableItem>::new);
-    let mut next_id = use_signal(|| 0);
+pub fn Table(
+    items: Signal<Vec<TableItem>>,
+    agenda: Agenda,
+    /// A sequence is being timed, which a meeting would replace.
+    sequencing: bool,
+    on_start: EventHandler<()>,
+) -> Element {
     let mut editing_id = use_signal(|| Option::<usize>::None);
     let mut drag_source = use_signal(|| Option::<usize>::None);
     let mut new_item_text = use_signal(String::new);
+    let mut importing = use_signal(|| false);
+    let mut template_name = use_signal(|| Option::<String>::None);
+    let mut save_template = move || {
+        let name = template_name.take().unwrap_or_default();
+        let mut templates = Templates::load();
+        if templates.save_as(&name, &items.read()) {
+            templates.save();
+        }
+    };
+    let mut import_text = use_signal(String::new);
+    let mut import = move |replace: bool| {
+        let first_id = match replace {
+            true => 0,
+            false => items.read().iter().map(|i| i.id + 1).max().unwrap_or(0),
+        };
+        let imported = parse_markdown(&import_text.read(), first_id);
+        if replace {
+            items.set(imported);
+        } else {
+            items.write().extend(imported);
+        }
+        import_text.set(String::new());
+        importing.set(false);
+    };
+    let export = move |script: &str| {
+        let _ = document::eval(script).send(to_markdown(&items.read()));
+    };
+    let can_start = !sequencing
+        && items
+            .read()
+            .iter()
+            .any(|item| !item.completed && item.timebox.is_some());
 
     rsx! {
         div { class: "w-full flex flex-col space-y-2 mt-8",
-            h2 { class: "text-2xl font-bold", "Table" }
+            div { class: "flex items-center gap-2",
+                h2 { class: "flex-grow text-2xl font-bold", "Table" }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1 text-sm",
+                    title: "Copy as a Markdown task list",
+                    onclick: move |_| export(COPY),
+                    "Copy"
+                }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1 text-sm",
+                    title: "Download as a Markdown file",
+                    onclick: move |_| export(DOWNLOAD),
+                    "Download"
+                }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1 text-sm",
+                    title: "Paste a Markdown task list",
+                    onclick: move |_| importing.toggle(),
+                    "Import"
+                }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1 text-sm",
+                    title: "Keep this list to start from in the lobby",
+                    onclick: move |_| template_name.set(Some(String::new())),
+                    "Save as template"
+                }
+                if can_start {
+                    button {
+                        class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold",
+                        title: "Time the open items with a timebox one after another",
+                        onclick: move |_| on_start.call(()),
+                        "Start meeting"
+                    }
+                }
+            }
+            if let Some(name) = template_name() {
+                div { class: "flex items-center gap-2 w-full",
+                    input {
+                        class: "flex-grow bg-slate-700 text-white p-2 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                        r#type: "text",
+                        placeholder: "Template name",
+                        autofocus: true,
+                        value: "{name}",
+                        oninput: move |e| template_name.set(Some(e.value())),
+                        onkeydown: move |e| match e.key() {
+                            Key::Enter => save_template(),
+                            Key::Escape => template_name.set(None),
+                            _ => {}
+                        },
+                    }
+                    button {
+                        class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                        onclick: move |_| template_name.set(None),
+                        "Cancel"
+                    }
+                    button {
+                        class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold disabled:opacity-50",
+                        disabled: name.trim().is_empty(),
+                        onclick: move |_| save_template(),
+                        "Save"
+                    }
+                }
+            }
+            if importing() {
+                div { class: "flex flex-col gap-2 w-full p-4 rounded-lg border border-slate-700 bg-slate-800",
+                    textarea {
+                        class: "w-full h-32 bg-slate-700 text-white p-2 rounded font-mono text-sm focus:outline-none focus:ring-2 focus:ring-blue-500",
+                        placeholder: "- [ ] Item\n- [x] Done item (2m)",
+                        value: "{import_text}",
+                        oninput: move |e| import_text.set(e.value()),
+                    }
+                    div { class: "flex justify-end gap-2",
+                        button {
+                            class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                            onclick: move |_| importing.set(false),
+                            "Cancel"
+                        }
+                        button {
+                            class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                            onclick: move |_| import(true),
+                            "Replace"
+                        }
+                        button {
+                            class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold",
+                            onclick: move |_| import(false),
+                            "Add"
+                        }
+                    }
+                }
+            }
             div { class: "w-full border border-slate-700 rounded-lg p-4 bg-slate-800",
                 for (index , item) in items.read().clone().into_iter().enumerate() {
                     div {
                         key: "{item.id}",
-                        class: "flex items-center space-x-2 py-2 border-b border-slate-700 last:border-b-0 group",
+                        class: if item.step.is_some() && item.step == agenda.step { "flex items-center space-x-2 py-2 border-b border-slate-700 last:border-b-0 group bg-slate-700 rounded" } else { "flex items-center space-x-2 py-2 border-b border-slate-700 last:border-b-0 group" },
                         draggable: "true",
                         ondragstart: move |_| drag_source.set(Some(index)),
                         ondragover: move |e| e.prevent_default(),
@@ -99,6 +356,39 @@ pub fn Table() -> Element {
                                 "{item.description}"
                             }
                         }
+                        match (item.timebox, item.step.and_then(|step| agenda.spent.get(step as usize))) {
+                            (Some(timebox), Some(&spent)) => rsx! {
+                                span {
+                                    class: if spent > timebox.0 { "font-mono text-sm text-red-400" } else { "font-mono text-sm text-slate-400" },
+                                    title: if spent > timebox.0 { format!("{} over", Limit(spent - timebox.0)) } else { String::new() },
+                                    "{Countdown(spent)} / {timebox}"
+                                }
+                            },
+                            (timebox, _) => rsx! {
+                                input {
+                                    class: "w-20 bg-slate-700 text-white text-sm p-1 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                                    r#type: "text",
+                                    placeholder: "timebox",
+                                    title: "Timebox, such as 2m or 1:30",
+                                    value: timebox.map(|timebox| timebox.to_string()).unwrap_or_default(),
+                                    onchange: move |e| {
+                                        let timebox = match e.value().trim() {
+                                            "" => None,
+                                            text => match text.parse::<Limit>() {
+                                                Ok(timebox) => Some(timebox),
+                                                Err(err) => {
+                                                    warn!("timebox: {err}");
+                                                    return;
+                                                }
+                                            },
+                                        };
+                                        if let Some(i) = items.write().iter_mut().find(|i| i.id == item.id) {
+                                            i.timebox = timebox;
+                                        }
+                                    },
+                                }
+                            },
+                        }
                         button {
                             class: "text-red-500 hover:text-red-400 font-bold px-2 invisible group-hover:visible",
                             onclick: move |_| items.write().retain(|i| i.id != item.id),
@@ -118,14 +408,15 @@ pub fn Table() -> Element {
                             if e.key() == Key::Enter {
                                 let val = new_item_text.read().clone();
                                 if !val.trim().is_empty() {
-                                    let id = *next_id.read();
-                                    *next_id.write() += 1;
+                                    let id = items.read().iter().map(|i| i.id + 1).max().unwrap_or(0);
                                     items
                                         .write()
                                         .push(TableItem {
                                             id,
                                             description: val.trim().to_string(),
                                             completed: false,
+                                            timebox: None,
+                                            step: None,
                                         });
                                     new_item_text.set(String::new());
                                 }
@@ -137,3 +428,108 @@ pub fn Table() -> Element {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: usize, completed: bool, timebox: Option<i64>) -> TableItem {
+        TableItem {
+            id,
+            description: format!("Item {id}"),
+            completed,
+            timebox: timebox.map(|secs| Limit(TimeDelta::seconds(secs))),
+            step: Some(9),
+        }
+    }
+
+    #[test]
+    fn test_agenda_plan_and_sync() {
+        let mut items = vec![
+            item(0, false, Some(60)),
+            item(1, true, Some(60)),
+            item(2, false, None),
+            item(3, false, Some(30)),
+        ];
+        let segments = plan_agenda(&mut items);
+        assert_eq!(
+            segments,
+            [
+                Segment::new("Item 0", TimeDelta::seconds(60)),
+                Segment::new("Item 3", TimeDelta::seconds(30)),
+            ]
+        );
+        let steps = items.iter().map(|item| item.step).collect::<Vec<_>>();
+        assert_eq!(steps, [Some(0), None, None, Some(1)]);
+
+        sync_agenda(&mut items, 1);
+        let completed = items.iter().map(|item| item.completed).collect::<Vec<_>>();
+        assert_eq!(completed, [true, true, false, false]);
+        // going back a step reopens its item but leaves the rest alone
+        sync_agenda(&mut items, 0);
+        let completed = items.iter().map(|item| item.completed).collect::<Vec<_>>();
+        assert_eq!(completed, [false, true, false, false]);
+
+        // once closed, the items are the user's to tick again
+        close_agenda(&mut items);
+        assert!(items.iter().all(|item| item.step.is_none()));
+        sync_agenda(&mut items, 5);
+        let completed = items.iter().map(|item| item.completed).collect::<Vec<_>>();
+        assert_eq!(completed, [false, true, false, false]);
+    }
+
+    #[test]
+    fn test_markdown_round_trip() {
+        let mut items = vec![item(0, false, None), item(1, true, Some(90))];
+        items[0].description = "Check (the) dice".to_string();
+        let markdown = to_markdown(&items);
+        assert_eq!(markdown, "- [ ] Check (the) dice\n- [x] Item 1 (1m30s)\n");
+
+        let parsed = parse_markdown(&markdown, 5);
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].id, 5);
+        assert_eq!(parsed[0].description, "Check (the) dice");
+        assert_eq!(parsed[0].timebox, None);
+        assert!(parsed[1].completed);
+        assert_eq!(parsed[1].timebox, Some(Limit(TimeDelta::seconds(90))));
+        assert_eq!(to_markdown(&parsed), markdown);
+    }
+
+    #[test]
+    fn test_markdown_keeps_durations_in_text() {
+        let mut items = vec![
+            item(0, false, None),
+            item(1, false, Some(300)),
+            item(2, false, None),
+        ];
+        items[0].description = "Walk (2m)".to_string();
+        items[1].description = "Stretch (30s)".to_string();
+        items[2].description = "Odd \\)".to_string();
+        let markdown = to_markdown(&items);
+        assert_eq!(
+            markdown,
+            "- [ ] Walk (2m\\)\n- [ ] Stretch (30s\\) (5m)\n- [ ] Odd \\\\)\n"
+        );
+        let parsed = parse_markdown(&markdown, 0);
+        let read = |items: &[TableItem]| {
+            items
+                .iter()
+                .map(|item| (item.description.clone(), item.timebox))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(read(&parsed), read(&items));
+        assert_eq!(to_markdown(&parsed), markdown);
+    }
+
+    #[test]
+    fn test_parse_markdown_leniently() {
+        let markdown = "# Standup\n\n* [X] Yesterday\n  - Blockers (soon)\n+ [ ]  \nNotes\n";
+        let parsed = parse_markdown(markdown, 0);
+        let summary = parsed
+            .iter()
+            .map(|item| (item.description.as_str(), item.completed))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [("Yesterday", true), ("Blockers (soon)", false)]);
+        assert_eq!(parsed[1].id, 1);
+    }
+}
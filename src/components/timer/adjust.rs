@@ -0,0 +1,247 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use super::{duration::Limit, snapshot::millis_option};
+
+/// Limits on the time each player may add to their own clock over a game.
+/// Taking time away is never limited.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Budget {
+    /// How many times time may be added, like a number of timeouts.
+    pub count: Option<u32>,
+    /// How much time may be added altogether.
+    #[serde(with = "millis_option")]
+    pub total: Option<TimeDelta>,
+}
+
+/// What is left of a player's [`Budget`]; `None` where it is unlimited.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Allowance {
+    pub count: Option<u32>,
+    pub total: Option<TimeDelta>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExtendError {
+    /// Every extension allowed has been used.
+    Count(u32),
+    /// All of the time allowed has been added.
+    Total(TimeDelta),
+}
+
+impl Display for ExtendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtendError::Count(1) => write!(f, "the one extension has been used"),
+            ExtendError::Count(count) => write!(f, "all {count} extensions have been used"),
+            ExtendError::Total(total) => {
+                write!(f, "all {} of extra time has been used", Limit(*total))
+            }
+        }
+    }
+}
+
+impl Budget {
+    /// What is left after `count` extensions adding up to `total`.
+    pub fn left(&self, count: u32, total: TimeDelta) -> Allowance {
+        Allowance {
+            count: self.count.map(|allowed| allowed.saturating_sub(count)),
+            total: self
+                .total
+                .map(|allowed| (allowed - total).max(TimeDelta::zero())),
+        }
+    }
+
+    /// How much of `delta` may be added after `count` extensions adding up
+    /// to `total`. The last of the time allowed is granted in part.
+    pub fn grant(
+        &self,
+        count: u32,
+        total: TimeDelta,
+        delta: TimeDelta,
+    ) -> Result<TimeDelta, ExtendError> {
+        let left = self.left(count, total);
+        match (left, self.count, self.total) {
+            (Allowance { count: Some(0), .. }, Some(allowed), _) => {
+                Err(ExtendError::Count(allowed))
+            }
+            (
+                Allowance {
+                    total: Some(rest), ..
+                },
+                _,
+                Some(allowed),
+            ) if rest <= TimeDelta::zero() => Err(ExtendError::Total(allowed)),
+            (
+                Allowance {
+                    total: Some(rest), ..
+                },
+                _,
+                _,
+            ) => Ok(delta.min(rest)),
+            _ => Ok(delta),
+        }
+    }
+}
+
+impl Allowance {
+    pub fn allows(&self, delta: TimeDelta) -> bool {
+        delta <= TimeDelta::zero()
+            || (self.count != Some(0) && self.total.is_none_or(|rest| rest > TimeDelta::zero()))
+    }
+}
+
+/// The buttons for adding or taking away time by hand, e.g. `-10s,+15s,+1m`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Adjustments(pub Vec<TimeDelta>);
+
+impl Default for Adjustments {
+    fn default() -> Self {
+        Self(vec![TimeDelta::seconds(30)])
+    }
+}
+
+impl Adjustments {
+    /// The first button adding time.
+    pub fn up(&self) -> Option<TimeDelta> {
+        self.0
+            .iter()
+            .copied()
+            .find(|delta| *delta > TimeDelta::zero())
+    }
+
+    /// The first button taking time away, or else the opposite of [`Self::up`].
+    pub fn down(&self) -> Option<TimeDelta> {
+        self.0
+            .iter()
+            .copied()
+            .find(|delta| *delta < TimeDelta::zero())
+            .or_else(|| self.up().map(|delta| -delta))
+    }
+}
+
+/// A button label such as `+1m` or `-10s`.
+pub fn label(delta: TimeDelta) -> String {
+    let sign = if delta < TimeDelta::zero() { '-' } else { '+' };
+    format!("{sign}{}", Limit(delta.abs()))
+}
+
+impl Display for Adjustments {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let labels = self.0.iter().map(|delta| label(*delta)).collect::<Vec<_>>();
+        write!(f, "{}", labels.join(","))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAdjustmentsError(String);
+
+impl Display for ParseAdjustmentsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid adjustment: {:?}", self.0)
+    }
+}
+
+impl FromStr for Adjustments {
+    type Err = ParseAdjustmentsError;
+
+    /// Parses a comma-separated list of durations, each taking time away if
+    /// it starts with `-`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let err = || ParseAdjustmentsError(part.to_string());
+                let (sign, magnitude) = match part.strip_prefix('-') {
+                    Some(rest) => (-1, rest),
+                    None => (1, part.strip_prefix('+').unwrap_or(part)),
+                };
+                let Limit(delta) = magnitude.parse().map_err(|_| err())?;
+                Ok(delta * sign)
+            })
+            .collect::<Result<_, _>>()
+            .map(Self)
+    }
+}
+
+impl From<Adjustments> for String {
+    fn from(adjustments: Adjustments) -> Self {
+        adjustments.to_string()
+    }
+}
+
+impl TryFrom<String> for Adjustments {
+    type Error = ParseAdjustmentsError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: i64) -> TimeDelta {
+        TimeDelta::seconds(secs)
+    }
+
+    #[test]
+    fn test_adjustments_round_trip() {
+        let adjustments = "-10s, +15, 1m".parse::<Adjustments>().unwrap();
+        assert_eq!(
+            adjustments,
+            Adjustments(vec![secs(-10), secs(15), secs(60)])
+        );
+        assert_eq!(adjustments.to_string(), "-10s,+15s,+1m");
+        assert_eq!(adjustments.to_string().parse(), Ok(adjustments.clone()));
+        assert_eq!(adjustments.up(), Some(secs(15)));
+        assert_eq!(adjustments.down(), Some(secs(-10)));
+
+        assert_eq!("".parse(), Ok(Adjustments(vec![])));
+        assert_eq!(Adjustments::default().down(), Some(secs(-30)));
+        assert!("+0s".parse::<Adjustments>().is_err());
+        assert!("+1x".parse::<Adjustments>().is_err());
+    }
+
+    #[test]
+    fn test_budget() {
+        let unlimited = Budget::default();
+        assert_eq!(unlimited.grant(10, secs(600), secs(30)), Ok(secs(30)));
+
+        let timeouts = Budget {
+            count: Some(3),
+            total: None,
+        };
+        assert_eq!(timeouts.grant(2, secs(60), secs(30)), Ok(secs(30)));
+        assert_eq!(
+            timeouts.grant(3, secs(90), secs(30)),
+            Err(ExtendError::Count(3))
+        );
+        assert!(!timeouts.left(3, secs(90)).allows(secs(30)));
+        assert!(timeouts.left(3, secs(90)).allows(secs(-10)));
+
+        // the last of the time allowed is granted in part
+        let capped = Budget {
+            count: None,
+            total: Some(secs(60)),
+        };
+        assert_eq!(capped.grant(1, secs(45), secs(30)), Ok(secs(15)));
+        assert_eq!(
+            capped.grant(2, secs(60), secs(30)),
+            Err(ExtendError::Total(secs(60)))
+        );
+        assert_eq!(
+            capped.left(1, secs(45)),
+            Allowance {
+                count: None,
+                total: Some(secs(15))
+            }
+        );
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::TimeDelta;
+
+use super::{
+    event::TimerEvent,
+    thresholds::{Level, Thresholds},
+};
+
+/// Watches the active player's time run down and reports each warning
+/// threshold and, past the first one or within the last `ticks` seconds, each
+/// whole second as it is crossed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alerts {
+    thresholds: Thresholds,
+    ticks: u32,
+    player: usize,
+    level: Level,
+    second: Option<i64>,
+}
+
+impl Alerts {
+    pub fn new(thresholds: Thresholds, ticks: u32) -> Self {
+        Self {
+            thresholds,
+            ticks,
+            player: 0,
+            level: Level::Normal,
+            second: None,
+        }
+    }
+
+    /// Only call this while the clock is running, so that a clock that
+    /// starts out below a threshold warns once it gets going.
+    pub fn update(&mut self, player: usize, remaining: TimeDelta) -> Vec<TimerEvent> {
+        if player != self.player {
+            *self = Self {
+                player,
+                ..Self::new(self.thresholds, self.ticks)
+            };
+        }
+        let mut events = Vec::new();
+        let level = self.thresholds.level(remaining);
+        let warning = matches!(level, Level::Warning | Level::Critical);
+        if warning && level > self.level {
+            events.push(TimerEvent::Warning(level));
+        }
+        self.level = level;
+
+        // rounded up, so the final second counts as one left
+        let second = (remaining.num_milliseconds() + 999).div_euclid(1000);
+        let ticking = (1..=i64::from(self.ticks)).contains(&second);
+        if (warning || ticking) && self.second.is_some_and(|last| second < last) {
+            events.push(TimerEvent::Second(second as u32));
+        }
+        self.second = Some(second);
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn millis(millis: i64) -> TimeDelta {
+        TimeDelta::milliseconds(millis)
+    }
+
+    #[test]
+    fn test_alerts() {
+        let mut alerts = Alerts::new(Thresholds::default(), 0);
+        assert_eq!(alerts.update(0, millis(30_500)), vec![]);
+        assert_eq!(
+            alerts.update(0, millis(30_000)),
+            vec![TimerEvent::Warning(Level::Warning), TimerEvent::Second(30)]
+        );
+        assert_eq!(alerts.update(0, millis(29_500)), vec![]);
+        assert_eq!(
+            alerts.update(0, millis(28_900)),
+            vec![TimerEvent::Second(29)]
+        );
+        assert_eq!(
+            alerts.update(0, millis(9_950)),
+            vec![TimerEvent::Warning(Level::Critical), TimerEvent::Second(10)]
+        );
+
+        // time's up is reported by the stopwatch itself
+        assert_eq!(alerts.update(0, millis(-100)), vec![]);
+        assert_eq!(alerts.update(0, millis(-1_100)), vec![]);
+
+        // an extension drops the level, so the warnings come again
+        assert_eq!(alerts.update(0, millis(40_000)), vec![]);
+        assert_eq!(
+            alerts.update(0, millis(25_000)),
+            vec![TimerEvent::Warning(Level::Warning), TimerEvent::Second(25)]
+        );
+    }
+
+    #[test]
+    fn test_alerts_reset_for_next_player() {
+        let mut alerts = Alerts::new(Thresholds::default(), 0);
+        alerts.update(0, millis(5_000));
+        // the next player is warned straight away, with no stale second
+        assert_eq!(
+            alerts.update(1, millis(20_000)),
+            vec![TimerEvent::Warning(Level::Warning)]
+        );
+        assert_eq!(
+            alerts.update(1, millis(19_000)),
+            vec![TimerEvent::Second(19)]
+        );
+    }
+
+    #[test]
+    fn test_ticks_without_warnings() {
+        let thresholds = Thresholds {
+            warning: TimeDelta::zero(),
+            critical: TimeDelta::zero(),
+        };
+        let mut alerts = Alerts::new(thresholds, 3);
+        assert_eq!(alerts.update(0, millis(4_500)), vec![]);
+        assert_eq!(alerts.update(0, millis(3_900)), vec![]);
+        assert_eq!(alerts.update(0, millis(2_900)), vec![TimerEvent::Second(3)]);
+        assert_eq!(alerts.update(0, millis(900)), vec![TimerEvent::Second(1)]);
+        assert_eq!(alerts.update(0, millis(-100)), vec![]);
+    }
+
+    #[test]
+    fn test_ticks_beyond_warning() {
+        let thresholds = Thresholds {
+            warning: TimeDelta::seconds(2),
+            critical: TimeDelta::zero(),
+        };
+        let mut alerts = Alerts::new(thresholds, 5);
+        alerts.update(0, millis(6_000));
+        assert_eq!(alerts.update(0, millis(4_900)), vec![TimerEvent::Second(5)]);
+    }
+}
@@ -0,0 +1,1530 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    adjust::{Allowance, Budget, ExtendError},
+    clock::Clock,
+    control::TimeControl,
+    cycle::{Cycle, Phase},
+    event::{PlayerEvent, TimerEvent, TimerState},
+    history::{History, Lap},
+    overtime::{Overtime, Standing},
+    sequence::{Advance, Segment, Sequence},
+    snapshot::{millis_option, text, BankSnapshot},
+    stopwatch::{Countdown, Stopwatch},
+    undo::{Command, Step, Undo},
+};
+
+/// The most phases or segments one [`Bank::tick`] moves on by itself, so that
+/// tiny ones after a long gap cannot hold up the page.
+const MAX_ADVANCES: usize = 100;
+
+/// How time is accounted for when the turn passes to the next player.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Every turn gets the full limit; passing the turn resets the clock.
+    #[default]
+    Turn,
+    /// Every player owns a bank of time for the whole game, like a chess clock.
+    Chess,
+    /// Two players share a fixed amount of time: whatever the active player
+    /// spends flows into the opponent's bank, like sand in an hourglass.
+    Hourglass,
+    /// A single clock moving through work and break phases by itself, as in
+    /// the Pomodoro technique.
+    Cycle,
+    /// A single clock timing a list of named segments in turn.
+    Sequence,
+}
+
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::Turn => write!(f, "turn"),
+            Mode::Chess => write!(f, "chess"),
+            Mode::Hourglass => write!(f, "hourglass"),
+            Mode::Cycle => write!(f, "cycle"),
+            Mode::Sequence => write!(f, "sequence"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseModeError(String);
+
+impl Display for ParseModeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown timer mode: {:?}", self.0)
+    }
+}
+
+impl FromStr for Mode {
+    type Err = ParseModeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "turn" => Ok(Mode::Turn),
+            "chess" => Ok(Mode::Chess),
+            "hourglass" => Ok(Mode::Hourglass),
+            "cycle" => Ok(Mode::Cycle),
+            "sequence" => Ok(Mode::Sequence),
+            _ => Err(ParseModeError(s.to_string())),
+        }
+    }
+}
+
+/// The rules a [`Bank`] enforces for every player.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    #[serde(with = "text")]
+    pub mode: Mode,
+    /// `None` to count up without a limit, which hourglass mode cannot do.
+    #[serde(with = "millis_option")]
+    pub limit: Option<TimeDelta>,
+    #[serde(with = "text")]
+    pub control: TimeControl,
+    #[serde(with = "text")]
+    pub overtime: Overtime,
+    /// Limits on the time each player may add to their clock by hand.
+    pub budget: Budget,
+    /// The phases of [`Mode::Cycle`], which ignores `limit`.
+    pub cycle: Cycle,
+    /// The segments of [`Mode::Sequence`], which ignores `limit` unless
+    /// there are none.
+    pub sequence: Sequence,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            mode: Mode::default(),
+            limit: Some(TimeDelta::seconds(90)),
+            control: TimeControl::default(),
+            overtime: Overtime::default(),
+            budget: Budget::default(),
+            cycle: Cycle::default(),
+            sequence: Sequence::default(),
+        }
+    }
+}
+
+/// One [`Stopwatch`] per player, of which exactly one is active at a time.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Bank<C: Clock> {
+    clock: C,
+    rules: Rules,
+    stopwatches: Vec<Stopwatch<C>>,
+    moves: Vec<Option<u32>>,
+    active: usize,
+    turn_start: TimeDelta,
+    turn_extension: TimeDelta,
+    history: History,
+    /// Phases completed in [`Mode::Cycle`], or segments in [`Mode::Sequence`].
+    step: u32,
+    events: Vec<PlayerEvent>,
+    undo: Undo,
+}
+
+impl<C: Clock + Clone> Bank<C> {
+    pub fn new(clock: C, rules: Rules, players: usize) -> Self {
+        let players = match rules.mode {
+            Mode::Hourglass => 2,
+            Mode::Cycle | Mode::Sequence => 1,
+            _ => players.max(1),
+        };
+        let limit = match rules.mode {
+            Mode::Cycle => Some(rules.cycle.duration(rules.cycle.phase(0))),
+            Mode::Sequence => match rules.sequence.segment(0) {
+                Some(segment) => Some(segment.duration),
+                None => rules.limit,
+            },
+            _ => rules.limit,
+        };
+        let (budget, delay) = (rules.budget, rules.control.delay());
+        let mut bank = Self {
+            clock: clock.clone(),
+            rules,
+            stopwatches: (0..players)
+                .map(|_| {
+                    match limit {
+                        Some(limit) => Stopwatch::new(clock.clone(), limit),
+                        None => Stopwatch::counting_up(clock.clone()),
+                    }
+                    .with_budget(budget)
+                })
+                .collect(),
+            moves: vec![None; players],
+            active: 0,
+            turn_start: TimeDelta::zero(),
+            turn_extension: TimeDelta::zero(),
+            history: History::default(),
+            step: 0,
+            events: Vec::new(),
+            undo: Undo::default(),
+        };
+        bank.active_mut().set_delay(delay);
+        bank
+    }
+
+    /// Takes back the latest command, returning it unless there was none.
+    pub fn undo(&mut self) -> Option<Command> {
+        let (command, before) = self.undo.undo(self.snapshot(), self.clock.now())?;
+        self.load(before);
+        Some(command)
+    }
+
+    pub fn redo(&mut self) -> Option<Command> {
+        let (command, after) = self.undo.redo(self.snapshot(), self.clock.now())?;
+        self.load(after);
+        Some(command)
+    }
+
+    /// Puts the bank in the state of `snapshot`, keeping its undo history and
+    /// any events not yet taken.
+    fn load(&mut self, snapshot: BankSnapshot) {
+        if let Some(mut bank) = Self::restore(self.clock.clone(), snapshot) {
+            bank.events = std::mem::take(&mut self.events);
+            bank.undo = std::mem::take(&mut self.undo);
+            *self = bank;
+        }
+    }
+
+    /// Rebuilds a bank from a snapshot, or `None` if the snapshot has no
+    /// players or its active player does not exist.
+    pub fn restore(clock: C, snapshot: BankSnapshot) -> Option<Self> {
+        let players = snapshot.stopwatches.len();
+        if snapshot.active >= players {
+            return None;
+        }
+        let mut moves = snapshot.moves;
+        moves.resize(players, None);
+        let budget = snapshot.rules.budget;
+        Some(Self {
+            clock: clock.clone(),
+            rules: snapshot.rules,
+            stopwatches: snapshot
+                .stopwatches
+                .into_iter()
+                .map(|stopwatch| Stopwatch::restore(clock.clone(), stopwatch).with_budget(budget))
+                .collect(),
+            moves,
+            active: snapshot.active,
+            turn_start: snapshot.turn_start,
+            turn_extension: snapshot.turn_extension,
+            history: snapshot.history,
+            step: snapshot.step,
+            events: Vec::new(),
+            undo: Undo::default(),
+        })
+    }
+}
+
+impl<C: Clock> Bank<C> {
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    pub fn active(&self) -> &Stopwatch<C> {
+        &self.stopwatches[self.active]
+    }
+
+    fn active_mut(&mut self) -> &mut Stopwatch<C> {
+        &mut self.stopwatches[self.active]
+    }
+
+    pub fn stopwatches(&self) -> &[Stopwatch<C>] {
+        &self.stopwatches
+    }
+
+    pub fn toggle(&mut self) {
+        let command = match self.running() {
+            true => Command::Pause,
+            false => Command::Start,
+        };
+        self.record(command, |bank| {
+            bank.active_mut().toggle();
+            bank.collect(bank.active);
+        });
+    }
+
+    /// Adds time to the active player's clock within their budget, returning
+    /// how much was added.
+    pub fn extend_time(&mut self, delta: TimeDelta) -> Result<TimeDelta, ExtendError> {
+        let before = self.snapshot();
+        let granted = self.active_mut().extend_time(delta)?;
+        self.turn_extension += granted;
+        self.collect(self.active);
+        self.undo
+            .push(Command::Extend(granted), before, self.clock.now());
+        Ok(granted)
+    }
+
+    /// What is left of the active player's extension budget.
+    pub fn allowance(&self) -> Allowance {
+        self.active().allowance()
+    }
+
+    /// Runs `action` as `command`, remembering the state before it for undo.
+    fn record<T>(&mut self, command: Command, action: impl FnOnce(&mut Self) -> T) -> T {
+        let before = self.snapshot();
+        let result = action(self);
+        self.undo.push(command, before, self.clock.now());
+        result
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.undo.can_undo()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.undo.can_redo()
+    }
+
+    /// The latest command done, undone or redone within `within` of now.
+    pub fn last_step(&self, within: TimeDelta) -> Option<Step> {
+        self.undo.last_since(self.clock.now() - within)
+    }
+
+    /// Checks every stopwatch for expiry.
+    pub fn tick(&mut self) {
+        self.check_expiry();
+        // the time run past the end starts the next phase, which may have
+        // ended too if nobody was looking
+        let mut advanced = 0;
+        while self.advances() && self.active().state() == TimerState::Expired {
+            self.events
+                .retain(|event| event.event != TimerEvent::Expired);
+            if advanced == MAX_ADVANCES {
+                // the rest is caught up on over the next ticks
+                break;
+            }
+            self.pass_turn(true);
+            self.check_expiry();
+            advanced += 1;
+        }
+    }
+
+    /// Whether the active clock running out moves on by itself: a cycle goes
+    /// straight to its next phase, and so does a sequence unless it waits for
+    /// a tap or is over.
+    fn advances(&self) -> bool {
+        match self.rules.mode {
+            Mode::Cycle => true,
+            Mode::Sequence => {
+                self.rules.sequence.advance == Advance::Auto
+                    && self
+                        .rules
+                        .sequence
+                        .segment(self.step.saturating_add(1))
+                        .is_some()
+            }
+            _ => false,
+        }
+    }
+
+    fn check_expiry(&mut self) {
+        for index in 0..self.stopwatches.len() {
+            self.stopwatches[index].tick();
+            self.collect(index);
+        }
+    }
+
+    /// The current phase of [`Mode::Cycle`] and which work interval it is in.
+    pub fn phase(&self) -> Option<(Phase, u32)> {
+        let cycle = self.rules.cycle;
+        (self.rules.mode == Mode::Cycle).then(|| (cycle.phase(self.step), cycle.count(self.step)))
+    }
+
+    /// The phase that follows the current one in [`Mode::Cycle`].
+    pub fn next_phase(&self) -> Option<Phase> {
+        (self.rules.mode == Mode::Cycle).then(|| self.rules.cycle.phase(self.step + 1))
+    }
+
+    /// The segments of [`Mode::Sequence`] and how many of them are done.
+    pub fn sequence(&self) -> Option<(&Sequence, u32)> {
+        (self.rules.mode == Mode::Sequence).then_some((&self.rules.sequence, self.step))
+    }
+
+    /// The segment being timed in [`Mode::Sequence`].
+    pub fn segment(&self) -> Option<&Segment> {
+        let (sequence, step) = self.sequence()?;
+        sequence.segment(step)
+    }
+
+    /// The segment after the current one in [`Mode::Sequence`].
+    pub fn next_segment(&self) -> Option<&Segment> {
+        let (sequence, step) = self.sequence()?;
+        sequence.segment(step.saturating_add(1))
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn snapshot(&self) -> BankSnapshot {
+        BankSnapshot {
+            rules: self.rules.clone(),
+            stopwatches: self.stopwatches.iter().map(Stopwatch::snapshot).collect(),
+            moves: self.moves.clone(),
+            active: self.active,
+            turn_start: self.turn_start,
+            turn_extension: self.turn_extension,
+            history: self.history.clone(),
+            step: self.step,
+        }
+    }
+
+    pub fn state(&self, index: usize) -> TimerState {
+        self.stopwatches[index].state()
+    }
+
+    /// Drains the events emitted since the last call, in order.
+    pub fn take_events(&mut self) -> Vec<PlayerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    fn collect(&mut self, player: usize) {
+        let events = self.stopwatches[player].take_events();
+        self.events.extend(
+            events
+                .into_iter()
+                .map(|event| PlayerEvent { player, event }),
+        );
+    }
+
+    pub fn running(&self) -> bool {
+        self.active().running()
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.rules.mode
+    }
+
+    /// Main time left on the player's clock at `index`, including the time the
+    /// active player is pouring into the other bank in hourglass mode.
+    fn remaining(&self, index: usize) -> Option<TimeDelta> {
+        let remaining = self.stopwatches[index].remaining()?;
+        if self.rules.mode == Mode::Hourglass && index != self.active {
+            Some(remaining + self.turn_elapsed())
+        } else {
+            Some(remaining)
+        }
+    }
+
+    pub fn standing(&self, index: usize) -> Standing {
+        match self.remaining(index) {
+            Some(remaining) => self.rules.overtime.standing(remaining, self.moves[index]),
+            None => Standing::Main,
+        }
+    }
+
+    /// Share of the total time held by the player at `index`.
+    pub fn sand(&self, index: usize) -> f32 {
+        let total: TimeDelta = (0..self.stopwatches.len())
+            .map(|i| self.remaining(i).unwrap_or_default().max(TimeDelta::zero()))
+            .sum();
+        if total <= TimeDelta::zero() {
+            return 0.0;
+        }
+        self.remaining(index)
+            .unwrap_or_default()
+            .max(TimeDelta::zero())
+            .as_seconds_f32()
+            / total.as_seconds_f32()
+    }
+
+    /// Share of the active player's time used, or `None` when counting up.
+    pub fn progress(&self) -> Option<f32> {
+        match self.standing(self.active) {
+            Standing::ByoYomi { remaining, .. } | Standing::Canadian { remaining, .. } => {
+                let period = self.rules.overtime.period().as_seconds_f32();
+                Some(1.0 - remaining.as_seconds_f32() / period)
+            }
+            Standing::Main | Standing::Lost => self.active().progress(),
+        }
+    }
+
+    /// Formats the time left on the player's clock at `index`, within the
+    /// current overtime period once main time has run out, or `None` when
+    /// counting up.
+    pub fn countdown(&self, index: usize) -> Option<Countdown> {
+        match self.standing(index) {
+            Standing::ByoYomi { remaining, .. } | Standing::Canadian { remaining, .. } => {
+                Some(Countdown(remaining))
+            }
+            Standing::Main | Standing::Lost => self.remaining(index).map(Countdown),
+        }
+    }
+
+    /// What the clock at `index` shows: the countdown, or else the time spent.
+    pub fn face(&self, index: usize) -> TimeDelta {
+        match self.countdown(index) {
+            Some(Countdown(remaining)) => remaining,
+            None => self.stopwatches[index].elapsed(),
+        }
+    }
+
+    /// Time the active player has spent in the current turn.
+    pub fn turn_elapsed(&self) -> TimeDelta {
+        self.active().elapsed() - self.turn_start
+    }
+
+    /// Ends the active player's turn and hands the clock to the next player,
+    /// returning the duration of the turn that just ended. The [`TimeControl`]
+    /// credits the finished turn and grants the delay of the next one.
+    pub fn lap(&mut self) -> TimeDelta {
+        self.record(Command::Lap, |bank| bank.pass_turn(false))
+    }
+
+    /// Hands the clock to the player at `index`, for when the wrong one is
+    /// running. Nothing is credited and no lap is recorded.
+    pub fn select(&mut self, index: usize) {
+        if index >= self.stopwatches.len() || index == self.active {
+            return;
+        }
+        self.record(Command::Select(index), |bank| {
+            bank.check_expiry();
+            let running = bank.running();
+            bank.active_mut().stop();
+            bank.active = index;
+            bank.turn_start = bank.active().elapsed();
+            bank.turn_extension = TimeDelta::zero();
+            let delay = bank.rules.control.delay();
+            bank.active_mut().set_delay(delay);
+            if running {
+                bank.active_mut().start();
+            }
+            // only stopping one clock to start another, so nothing to report
+            for stopwatch in &mut bank.stopwatches {
+                stopwatch.take_events();
+            }
+        });
+    }
+
+    /// Laps the active clock, when its time ran out if carrying the rest over.
+    fn lap_active(&mut self, carry: bool) -> TimeDelta {
+        match self.active().total() {
+            Some(total) if carry => self.active_mut().lap_at(total),
+            _ => self.active_mut().lap(),
+        }
+    }
+
+    /// Ends the active turn. With `carry`, the turn ends when its time ran out
+    /// and the time since is carried into the next one.
+    fn pass_turn(&mut self, carry: bool) -> TimeDelta {
+        self.check_expiry();
+        let finished = self.active;
+        let mut running = self.running();
+        let overrun = match carry {
+            true => TimeDelta::zero(),
+            false => self
+                .remaining(finished)
+                .map_or(TimeDelta::zero(), |left| -left),
+        };
+        let turn = match self.rules.mode {
+            Mode::Turn => {
+                self.moves[self.active] = None;
+                self.active_mut().lap()
+            }
+            Mode::Cycle => {
+                self.step = self.step.saturating_add(1);
+                let cycle = self.rules.cycle;
+                let next = cycle.duration(cycle.phase(self.step));
+                let turn = self.lap_active(carry);
+                self.active_mut().set_limit(next);
+                turn
+            }
+            Mode::Sequence => {
+                let turn = self.lap_active(carry);
+                self.step = self.step.saturating_add(1).min(self.rules.sequence.steps());
+                match self.rules.sequence.segment(self.step) {
+                    Some(segment) => {
+                        let next = segment.duration;
+                        self.active_mut().set_limit(next);
+                    }
+                    // the last segment is done, so the clock stays stopped
+                    None => running = false,
+                }
+                turn
+            }
+            Mode::Chess | Mode::Hourglass => {
+                let turn = self.turn_elapsed();
+                if self.rules.mode == Mode::Hourglass {
+                    let next = (self.active + 1) % self.stopwatches.len();
+                    self.stopwatches[next].credit(turn);
+                }
+                if let Some(remaining) = self.active().remaining() {
+                    let (refund, moves) = self
+                        .rules
+                        .overtime
+                        .end_turn(remaining, self.moves[self.active]);
+                    self.moves[self.active] = moves;
+                    self.active_mut().credit(refund);
+                }
+                let credit = self.rules.control.credit(turn);
+                self.active_mut().credit(credit);
+                turn
+            }
+        };
+        self.active_mut().stop();
+
+        self.active = (self.active + 1) % self.stopwatches.len();
+        // time carried over is already part of the new turn
+        self.turn_start = match carry {
+            true => TimeDelta::zero(),
+            false => self.active().elapsed(),
+        };
+        let delay = self.rules.control.delay();
+        self.active_mut().set_delay(delay);
+        if running {
+            self.active_mut().start();
+        }
+
+        self.history.push(Lap {
+            player: finished,
+            duration: turn,
+            overrun: overrun.clamp(TimeDelta::zero(), turn.max(TimeDelta::zero())),
+            extension: self.turn_extension,
+            finished: self.clock.now(),
+        });
+        self.turn_extension = TimeDelta::zero();
+
+        // the bookkeeping above is reported as a single event
+        for stopwatch in &mut self.stopwatches {
+            stopwatch.take_events();
+        }
+        self.events.push(PlayerEvent {
+            player: finished,
+            event: TimerEvent::Lapped(turn),
+        });
+        turn
+    }
+}
+
+impl<C: Clock> Display for Bank<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Countdown(self.face(self.active)).fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::MockClock;
+    use super::*;
+
+    #[test]
+    fn test_mode_round_trip() {
+        for mode in [
+            Mode::Turn,
+            Mode::Chess,
+            Mode::Hourglass,
+            Mode::Cycle,
+            Mode::Sequence,
+        ] {
+            assert_eq!(mode.to_string().parse::<Mode>(), Ok(mode));
+        }
+        assert!("blitz".parse::<Mode>().is_err());
+    }
+
+    #[test]
+    fn test_turn_mode_resets_each_turn() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Turn,
+                limit: Some(TimeDelta::seconds(10)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(3);
+        assert_eq!(bank.to_string(), "00:07");
+
+        assert_eq!(bank.lap(), TimeDelta::seconds(3));
+        assert_eq!(bank.active_index(), 1);
+        assert!(bank.running());
+        assert_eq!(bank.to_string(), "00:10");
+
+        clock += TimeDelta::seconds(4);
+        assert_eq!(bank.lap(), TimeDelta::seconds(4));
+        assert_eq!(bank.active_index(), 0);
+
+        // the first player's turn starts from the full limit again
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(10)));
+        assert_eq!(
+            bank.stopwatches()[1].remaining(),
+            Some(TimeDelta::seconds(10))
+        );
+    }
+
+    #[test]
+    fn test_chess_mode_keeps_banks() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            3,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.lap(), TimeDelta::seconds(5));
+
+        // the previous player's clock is stopped and keeps its remaining time
+        clock += TimeDelta::seconds(7);
+        assert!(!bank.stopwatches()[0].running());
+        assert_eq!(
+            bank.stopwatches()[0].remaining(),
+            Some(TimeDelta::seconds(55))
+        );
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(53)));
+
+        assert_eq!(bank.lap(), TimeDelta::seconds(7));
+        clock += TimeDelta::seconds(1);
+        assert_eq!(bank.lap(), TimeDelta::seconds(1));
+
+        // back to the first player, whose bank continues from where it stopped
+        assert_eq!(bank.active_index(), 0);
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.turn_elapsed(), TimeDelta::seconds(5));
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(50)));
+        assert_eq!(bank.to_string(), "00:50");
+    }
+
+    #[test]
+    fn test_lap_while_paused() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(5);
+        bank.toggle();
+
+        // passing the turn while paused does not start the next player's clock
+        assert_eq!(bank.lap(), TimeDelta::seconds(5));
+        assert!(!bank.running());
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(60)));
+    }
+
+    #[test]
+    fn test_extension_applies_to_active_player() {
+        let clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.extend_time(TimeDelta::seconds(30)).unwrap();
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(90)));
+        assert_eq!(
+            bank.stopwatches()[1].remaining(),
+            Some(TimeDelta::seconds(60))
+        );
+    }
+
+    #[test]
+    fn test_fischer_increment() {
+        let mut clock = MockClock::new(0, 0);
+
+        let control = TimeControl::Fischer(TimeDelta::seconds(5));
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                control,
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(8);
+        assert_eq!(bank.lap(), TimeDelta::seconds(8));
+
+        // the increment is added once the move is made
+        assert_eq!(
+            bank.stopwatches()[0].remaining(),
+            Some(TimeDelta::seconds(57))
+        );
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(60)));
+    }
+
+    #[test]
+    fn test_bronstein_refund() {
+        let mut clock = MockClock::new(0, 0);
+
+        let control = TimeControl::Bronstein(TimeDelta::seconds(5));
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                control,
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+
+        // a quick move is refunded completely
+        clock += TimeDelta::seconds(3);
+        bank.lap();
+        assert_eq!(
+            bank.stopwatches()[0].remaining(),
+            Some(TimeDelta::seconds(60))
+        );
+
+        // a slow move is refunded up to the limit
+        clock += TimeDelta::seconds(8);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(52)));
+        bank.lap();
+        assert_eq!(
+            bank.stopwatches()[1].remaining(),
+            Some(TimeDelta::seconds(57))
+        );
+    }
+
+    #[test]
+    fn test_us_delay() {
+        let mut clock = MockClock::new(0, 0);
+
+        let control = TimeControl::Delay(TimeDelta::seconds(5));
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                control,
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+
+        // the first move starts with a delay, too
+        clock += TimeDelta::seconds(4);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(60)));
+        clock += TimeDelta::seconds(3);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(58)));
+        assert_eq!(bank.lap(), TimeDelta::seconds(2));
+
+        // the delay is granted again on every turn
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(60)));
+        bank.lap();
+        assert_eq!(bank.active().delay_remaining(), TimeDelta::seconds(5));
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(58)));
+    }
+
+    #[test]
+    fn test_us_delay_per_turn_mode() {
+        let mut clock = MockClock::new(0, 0);
+
+        let control = TimeControl::Delay(TimeDelta::seconds(2));
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Turn,
+                limit: Some(TimeDelta::seconds(10)),
+                control,
+                ..Default::default()
+            },
+            1,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.lap(), TimeDelta::seconds(3));
+
+        clock += TimeDelta::seconds(3);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(9)));
+    }
+
+    #[test]
+    fn test_byo_yomi_periods() {
+        let mut clock = MockClock::new(0, 0);
+
+        let overtime = Overtime::ByoYomi {
+            periods: 2,
+            period: TimeDelta::seconds(10),
+        };
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(5)),
+                overtime,
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+
+        // main time runs out and the first period starts
+        clock += TimeDelta::seconds(8);
+        assert_eq!(
+            bank.standing(0),
+            Standing::ByoYomi {
+                remaining: TimeDelta::seconds(7),
+                periods: 2
+            }
+        );
+        assert_eq!(bank.to_string(), "00:07");
+
+        // moving within the period keeps it
+        bank.lap();
+        assert_eq!(bank.countdown(0).unwrap().to_string(), "00:10");
+
+        clock += TimeDelta::seconds(1);
+        bank.lap();
+
+        // letting a period expire uses it up
+        clock += TimeDelta::seconds(12);
+        assert_eq!(
+            bank.standing(0),
+            Standing::ByoYomi {
+                remaining: TimeDelta::seconds(8),
+                periods: 1
+            }
+        );
+        bank.lap();
+        assert_eq!(bank.countdown(0).unwrap().to_string(), "00:10");
+
+        // the last period expiring loses the game
+        clock += TimeDelta::seconds(1);
+        bank.lap();
+        clock += TimeDelta::seconds(10);
+        assert_eq!(bank.standing(0), Standing::Lost);
+    }
+
+    #[test]
+    fn test_canadian_moves() {
+        let mut clock = MockClock::new(0, 0);
+
+        let overtime = Overtime::Canadian {
+            moves: 2,
+            period: TimeDelta::seconds(30),
+        };
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(5)),
+                overtime,
+                ..Default::default()
+            },
+            1,
+        );
+        bank.toggle();
+
+        clock += TimeDelta::seconds(15);
+        bank.lap();
+        assert_eq!(
+            bank.standing(0),
+            Standing::Canadian {
+                remaining: TimeDelta::seconds(20),
+                moves: 1
+            }
+        );
+
+        // completing the moves starts a fresh period
+        clock += TimeDelta::seconds(10);
+        bank.lap();
+        assert_eq!(
+            bank.standing(0),
+            Standing::Canadian {
+                remaining: TimeDelta::seconds(30),
+                moves: 2
+            }
+        );
+
+        clock += TimeDelta::seconds(30);
+        assert_eq!(bank.standing(0), Standing::Lost);
+    }
+
+    #[test]
+    fn test_hourglass_transfers_time() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Hourglass,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            5,
+        );
+        assert_eq!(bank.stopwatches().len(), 2);
+        assert_eq!(bank.sand(0), 0.5);
+        bank.toggle();
+
+        // the sand flows to the opponent while the active player thinks
+        clock += TimeDelta::seconds(15);
+        assert_eq!(bank.countdown(0).unwrap().to_string(), "00:45");
+        assert_eq!(bank.countdown(1).unwrap().to_string(), "01:15");
+        assert_eq!(bank.sand(1), 0.625);
+
+        // and stays there once the turn passes
+        assert_eq!(bank.lap(), TimeDelta::seconds(15));
+        assert_eq!(bank.countdown(0).unwrap().to_string(), "00:45");
+        assert_eq!(bank.countdown(1).unwrap().to_string(), "01:15");
+
+        clock += TimeDelta::seconds(30);
+        assert_eq!(bank.countdown(0).unwrap().to_string(), "01:15");
+        assert_eq!(bank.countdown(1).unwrap().to_string(), "00:45");
+        bank.lap();
+
+        // the total amount of time never changes
+        clock += TimeDelta::seconds(10);
+        let total = bank.remaining(0).unwrap() + bank.remaining(1).unwrap();
+        assert_eq!(total, TimeDelta::seconds(120));
+    }
+
+    #[test]
+    fn test_bank_events() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(10)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        bank.extend_time(TimeDelta::seconds(5)).unwrap();
+        clock += TimeDelta::seconds(3);
+        bank.lap();
+
+        let event = |player, event| PlayerEvent { player, event };
+        assert_eq!(
+            bank.take_events(),
+            vec![
+                event(0, TimerEvent::Started),
+                event(0, TimerEvent::Extended(TimeDelta::seconds(5))),
+                event(0, TimerEvent::Lapped(TimeDelta::seconds(3))),
+            ]
+        );
+        assert_eq!(bank.state(0), TimerState::Paused);
+        assert_eq!(bank.state(1), TimerState::Running);
+
+        // a player whose bank runs out expires once, even across turns
+        clock += TimeDelta::seconds(12);
+        bank.tick();
+        bank.tick();
+        bank.lap();
+        assert_eq!(
+            bank.take_events(),
+            vec![
+                event(1, TimerEvent::Expired),
+                event(1, TimerEvent::Lapped(TimeDelta::seconds(12))),
+            ]
+        );
+        assert_eq!(bank.state(1), TimerState::Expired);
+        assert_eq!(bank.state(0), TimerState::Running);
+    }
+
+    #[test]
+    fn test_lap_history() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Turn,
+                limit: Some(TimeDelta::seconds(10)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(4);
+        bank.lap();
+
+        bank.extend_time(TimeDelta::seconds(5)).unwrap();
+        clock += TimeDelta::seconds(20);
+        bank.lap();
+
+        let laps = bank.history().laps();
+        assert_eq!(laps.len(), 2);
+        assert_eq!(laps[0].player, 0);
+        assert_eq!(laps[0].duration, TimeDelta::seconds(4));
+        assert_eq!(laps[0].overrun, TimeDelta::zero());
+        assert_eq!(laps[0].finished, MockClock::new(4, 0).now());
+
+        assert_eq!(laps[1].player, 1);
+        assert_eq!(laps[1].duration, TimeDelta::seconds(20));
+        assert_eq!(laps[1].overrun, TimeDelta::seconds(5));
+        assert_eq!(laps[1].extension, TimeDelta::seconds(5));
+        assert_eq!(laps[1].finished, MockClock::new(24, 0).now());
+    }
+
+    #[test]
+    fn test_snapshot_restores_bank() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                control: TimeControl::Delay(TimeDelta::seconds(2)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(5);
+        bank.lap();
+        bank.extend_time(TimeDelta::seconds(10)).unwrap();
+        clock += TimeDelta::seconds(4);
+
+        let snapshot = bank.snapshot();
+        let mut restored = Bank::restore(clock.clone(), snapshot.clone()).unwrap();
+        assert_eq!(restored.snapshot(), snapshot);
+
+        // the running clock keeps counting across the restore
+        clock += TimeDelta::seconds(1);
+        assert!(restored.running());
+        assert_eq!(restored.active_index(), 1);
+        assert_eq!(restored.active().remaining(), Some(TimeDelta::seconds(67)));
+        assert_eq!(restored.lap(), TimeDelta::seconds(3));
+        assert_eq!(restored.history().laps().len(), 2);
+        assert_eq!(
+            restored.history().laps()[1].extension,
+            TimeDelta::seconds(10)
+        );
+
+        let empty = BankSnapshot {
+            stopwatches: Vec::new(),
+            ..snapshot
+        };
+        assert!(Bank::restore(clock, empty).is_none());
+    }
+
+    #[test]
+    fn test_undo_lap_keeps_clock_running() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Turn,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(10);
+        bank.lap();
+        assert_eq!(
+            bank.last_step(TimeDelta::seconds(5)),
+            Some(Step::Done(Command::Lap))
+        );
+
+        // a mis-tap noticed a few seconds later
+        clock += TimeDelta::seconds(3);
+        assert_eq!(bank.undo(), Some(Command::Lap));
+        assert_eq!(bank.active_index(), 0);
+        assert!(bank.running());
+        assert!(bank.history().laps().is_empty());
+        // the first player's clock never stopped
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(47)));
+
+        clock += TimeDelta::seconds(2);
+        assert_eq!(bank.redo(), Some(Command::Lap));
+        assert_eq!(bank.active_index(), 1);
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(55)));
+        assert_eq!(bank.history().laps().len(), 1);
+        assert!(!bank.can_redo());
+
+        clock += TimeDelta::seconds(10);
+        assert_eq!(bank.last_step(TimeDelta::seconds(5)), None);
+    }
+
+    #[test]
+    fn test_select_player_is_undoable() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::seconds(60)),
+                ..Default::default()
+            },
+            3,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(10);
+        bank.select(2);
+        assert_eq!(bank.active_index(), 2);
+        assert!(bank.running());
+        assert!(bank.history().laps().is_empty());
+        assert_eq!(
+            bank.last_step(TimeDelta::zero()),
+            Some(Step::Done(Command::Select(2)))
+        );
+        // selecting the active player or one out of range does nothing
+        bank.select(2);
+        bank.select(5);
+        assert_eq!(bank.undo(), Some(Command::Select(2)));
+
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.active_index(), 0);
+        assert!(bank.running());
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(45)));
+        assert_eq!(
+            bank.stopwatches()[2].remaining(),
+            Some(TimeDelta::seconds(60))
+        );
+
+        assert_eq!(bank.redo(), Some(Command::Select(2)));
+        assert_eq!(bank.active_index(), 2);
+        assert_eq!(
+            bank.stopwatches()[0].remaining(),
+            Some(TimeDelta::seconds(50))
+        );
+    }
+
+    #[test]
+    fn test_undo_extension_and_toggle() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(clock.clone(), Rules::default(), 1);
+        bank.toggle();
+        clock += TimeDelta::seconds(5);
+        bank.extend_time(TimeDelta::seconds(30)).unwrap();
+        bank.toggle();
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(115)));
+
+        assert_eq!(bank.undo(), Some(Command::Pause));
+        assert!(bank.running());
+        assert_eq!(bank.undo(), Some(Command::Extend(TimeDelta::seconds(30))));
+        assert_eq!(bank.active().remaining(), Some(TimeDelta::seconds(85)));
+
+        // a new command forgets whatever was undone
+        bank.extend_time(TimeDelta::seconds(10)).unwrap();
+        assert!(!bank.can_redo());
+        assert_eq!(bank.redo(), None);
+        assert_eq!(
+            bank.last_step(TimeDelta::zero()),
+            Some(Step::Done(Command::Extend(TimeDelta::seconds(10))))
+        );
+    }
+
+    #[test]
+    fn test_budget_is_per_player_and_undoable() {
+        let clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                budget: Budget {
+                    count: Some(1),
+                    total: None,
+                },
+                ..Default::default()
+            },
+            2,
+        );
+        assert_eq!(
+            bank.extend_time(TimeDelta::seconds(30)),
+            Ok(TimeDelta::seconds(30))
+        );
+        assert_eq!(
+            bank.extend_time(TimeDelta::seconds(30)),
+            Err(ExtendError::Count(1))
+        );
+        assert!(!bank.allowance().allows(TimeDelta::seconds(30)));
+
+        // undoing gives the extension back
+        bank.undo();
+        assert_eq!(bank.allowance().count, Some(1));
+
+        bank.lap();
+        assert_eq!(
+            bank.extend_time(TimeDelta::seconds(10)),
+            Ok(TimeDelta::seconds(10))
+        );
+    }
+
+    #[test]
+    fn test_count_up() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Chess,
+                limit: None,
+                overtime: Overtime::ByoYomi {
+                    periods: 1,
+                    period: TimeDelta::seconds(10),
+                },
+                ..Default::default()
+            },
+            2,
+        );
+        bank.toggle();
+        clock += TimeDelta::seconds(200);
+        bank.tick();
+        assert!(bank.countdown(0).is_none());
+        assert_eq!(bank.progress(), None);
+        assert_eq!(bank.standing(0), Standing::Main);
+        assert_eq!(bank.to_string(), "03:20");
+
+        assert_eq!(bank.lap(), TimeDelta::seconds(200));
+        assert_eq!(bank.history().laps()[0].overrun, TimeDelta::zero());
+        clock += TimeDelta::seconds(5);
+        assert_eq!(bank.face(0), TimeDelta::seconds(200));
+        assert_eq!(bank.face(1), TimeDelta::seconds(5));
+
+        // the missing limit survives a snapshot
+        let restored = Bank::restore(clock.clone(), bank.snapshot()).unwrap();
+        assert!(restored.countdown(1).is_none());
+    }
+
+    #[test]
+    fn test_cycle_advances_by_itself() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Cycle,
+                cycle: Cycle {
+                    work: TimeDelta::seconds(10),
+                    short_break: TimeDelta::seconds(2),
+                    long_break: TimeDelta::seconds(5),
+                    every: 2,
+                },
+                ..Default::default()
+            },
+            3,
+        );
+        assert_eq!(bank.stopwatches().len(), 1);
+        assert_eq!(bank.phase(), Some((Phase::Work, 1)));
+        assert_eq!(bank.next_phase(), Some(Phase::ShortBreak));
+        bank.toggle();
+        clock += TimeDelta::seconds(10);
+        bank.tick();
+        assert_eq!(bank.phase(), Some((Phase::ShortBreak, 1)));
+        assert!(bank.running());
+        assert_eq!(bank.to_string(), "00:02");
+        // the phase ending is a turn, not the clock running out
+        let events = bank.take_events();
+        assert!(events.iter().all(|e| e.event != TimerEvent::Expired));
+        assert!(events
+            .iter()
+            .any(|e| matches!(e.event, TimerEvent::Lapped(_))));
+
+        clock += TimeDelta::seconds(2);
+        bank.tick();
+        assert_eq!(bank.phase(), Some((Phase::Work, 2)));
+        // skipping ahead by hand works too
+        bank.lap();
+        assert_eq!(bank.phase(), Some((Phase::LongBreak, 2)));
+        assert_eq!(bank.to_string(), "00:05");
+
+        let restored = Bank::restore(clock.clone(), bank.snapshot()).unwrap();
+        assert_eq!(restored.phase(), Some((Phase::LongBreak, 2)));
+        assert_eq!(restored.to_string(), "00:05");
+    }
+
+    #[test]
+    fn test_cycle_carries_overshoot() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Cycle,
+                cycle: Cycle {
+                    work: TimeDelta::seconds(10),
+                    short_break: TimeDelta::seconds(2),
+                    long_break: TimeDelta::seconds(5),
+                    every: 2,
+                },
+                ..Default::default()
+            },
+            1,
+        );
+        bank.toggle();
+        // a late tick starts the break with the time already run past the work
+        clock += TimeDelta::seconds(11);
+        bank.tick();
+        assert_eq!(bank.phase(), Some((Phase::ShortBreak, 1)));
+        assert_eq!(bank.to_string(), "00:01");
+        assert_eq!(bank.history().laps()[0].duration, TimeDelta::seconds(10));
+        assert_eq!(bank.history().laps()[0].overrun, TimeDelta::zero());
+
+        // and a long gap passes over every phase that ended during it
+        clock += TimeDelta::seconds(1 + 10 + 3);
+        bank.tick();
+        assert_eq!(bank.phase(), Some((Phase::LongBreak, 2)));
+        assert_eq!(bank.to_string(), "00:02");
+        assert_eq!(bank.history().laps().len(), 3);
+        assert!(bank.running());
+        assert!(bank
+            .take_events()
+            .iter()
+            .all(|e| e.event != TimerEvent::Expired));
+
+        clock += TimeDelta::seconds(1);
+        bank.lap();
+        assert_eq!(bank.history().laps()[3].duration, TimeDelta::seconds(4));
+    }
+
+    #[test]
+    fn test_tick_advances_a_bounded_number_of_times() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Cycle,
+                cycle: Cycle {
+                    work: TimeDelta::milliseconds(1),
+                    short_break: TimeDelta::milliseconds(1),
+                    long_break: TimeDelta::milliseconds(1),
+                    every: 2,
+                },
+                ..Default::default()
+            },
+            1,
+        );
+        bank.toggle();
+        clock += TimeDelta::hours(1);
+        bank.tick();
+        assert_eq!(bank.history().laps().len(), MAX_ADVANCES);
+        assert!(bank
+            .take_events()
+            .iter()
+            .all(|e| e.event != TimerEvent::Expired));
+        bank.tick();
+        assert_eq!(bank.history().laps().len(), 2 * MAX_ADVANCES);
+    }
+
+    fn sequence_bank(clock: &MockClock, advance: Advance) -> Bank<MockClock> {
+        Bank::new(
+            clock.clone(),
+            Rules {
+                mode: Mode::Sequence,
+                sequence: Sequence {
+                    segments: vec![
+                        Segment::new("Opening", TimeDelta::seconds(10)),
+                        Segment::new("Closing", TimeDelta::seconds(5)),
+                    ],
+                    advance,
+                    rounds: 1,
+                },
+                ..Default::default()
+            },
+            2,
+        )
+    }
+
+    #[test]
+    fn test_sequence_advances_by_itself() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = sequence_bank(&clock, Advance::Auto);
+        assert_eq!(bank.stopwatches().len(), 1);
+        assert_eq!(bank.segment().unwrap().name, "Opening");
+        assert_eq!(bank.next_segment().unwrap().name, "Closing");
+        bank.toggle();
+        clock += TimeDelta::seconds(10);
+        bank.tick();
+        assert_eq!(bank.segment().unwrap().name, "Closing");
+        assert_eq!(bank.next_segment(), None);
+        assert_eq!(bank.to_string(), "00:05");
+        assert!(bank.running());
+
+        // the last segment runs out like any other clock
+        clock += TimeDelta::seconds(6);
+        bank.tick();
+        assert_eq!(bank.segment().unwrap().name, "Closing");
+        assert_eq!(bank.state(0), TimerState::Expired);
+        assert!(bank
+            .take_events()
+            .iter()
+            .any(|e| e.event == TimerEvent::Expired));
+
+        // and passing it finishes the sequence with the clock stopped
+        bank.lap();
+        assert_eq!(bank.segment(), None);
+        assert!(!bank.running());
+        let restored = Bank::restore(clock.clone(), bank.snapshot()).unwrap();
+        assert_eq!(restored.segment(), None);
+    }
+
+    #[test]
+    fn test_sequence_carries_overshoot() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = sequence_bank(&clock, Advance::Auto);
+        bank.rules.sequence.rounds = 2;
+        bank.toggle();
+        clock += TimeDelta::seconds(12);
+        bank.tick();
+        assert_eq!(bank.segment().unwrap().name, "Closing");
+        assert_eq!(bank.to_string(), "00:03");
+        assert_eq!(bank.history().laps()[0].duration, TimeDelta::seconds(10));
+
+        // the closing ends unseen and the next round's opening is under way
+        clock += TimeDelta::seconds(7);
+        bank.tick();
+        assert_eq!(bank.sequence().map(|(_, step)| step), Some(2));
+        assert_eq!(bank.to_string(), "00:06");
+
+        // the very last segment runs out instead of carrying on
+        clock += TimeDelta::seconds(20);
+        bank.tick();
+        assert_eq!(bank.segment().unwrap().name, "Closing");
+        assert_eq!(bank.sequence().map(|(_, step)| step), Some(3));
+        assert_eq!(bank.state(0), TimerState::Expired);
+        assert!(bank
+            .take_events()
+            .iter()
+            .any(|e| e.event == TimerEvent::Expired));
+    }
+
+    #[test]
+    fn test_sequence_waits_for_tap() {
+        let mut clock = MockClock::new(0, 0);
+        let mut bank = sequence_bank(&clock, Advance::Tap);
+        bank.toggle();
+        clock += TimeDelta::seconds(12);
+        bank.tick();
+        assert_eq!(bank.segment().unwrap().name, "Opening");
+        assert_eq!(bank.state(0), TimerState::Expired);
+
+        assert_eq!(bank.lap(), TimeDelta::seconds(12));
+        assert_eq!(bank.history().laps()[0].overrun, TimeDelta::seconds(2));
+        assert_eq!(bank.segment().unwrap().name, "Closing");
+        assert_eq!(bank.to_string(), "00:05");
+        assert!(bank.running());
+
+        bank.undo();
+        assert_eq!(bank.segment().unwrap().name, "Opening");
+    }
+}
@@ -0,0 +1,150 @@
+use chrono::{DateTime, Utc};
+
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UtcClock;
+
+impl UtcClock {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Clock for UtcClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Reads the wall clock once, then only moves forward by a monotonic timer,
+/// so changes to the system time cannot make the countdown jump. Clones share
+/// the same anchor and agree with each other.
+#[derive(Debug, Clone, Copy)]
+pub struct MonotonicClock {
+    anchor: DateTime<Utc>,
+    origin: monotonic::Mark,
+}
+
+impl MonotonicClock {
+    pub fn new() -> Self {
+        Self {
+            anchor: UtcClock::new().now(),
+            origin: monotonic::Mark::now(),
+        }
+    }
+}
+
+impl Default for MonotonicClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MonotonicClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.anchor + self.origin.elapsed()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+mod monotonic {
+    use std::time::Instant;
+
+    use chrono::TimeDelta;
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct Mark(Instant);
+
+    impl Mark {
+        pub fn now() -> Self {
+            Self(Instant::now())
+        }
+
+        pub fn elapsed(&self) -> TimeDelta {
+            TimeDelta::from_std(self.0.elapsed()).unwrap_or(TimeDelta::MAX)
+        }
+    }
+}
+
+/// `Instant` is not available in the browser, where `performance.now()` is.
+#[cfg(target_arch = "wasm32")]
+mod monotonic {
+    use chrono::TimeDelta;
+
+    #[derive(Debug, Clone, Copy)]
+    pub struct Mark(f64);
+
+    fn performance_now() -> f64 {
+        web_sys::window()
+            .and_then(|window| window.performance())
+            .map_or(0.0, |performance| performance.now())
+    }
+
+    impl Mark {
+        pub fn now() -> Self {
+            Self(performance_now())
+        }
+
+        pub fn elapsed(&self) -> TimeDelta {
+            TimeDelta::microseconds(((performance_now() - self.0) * 1000.0) as i64)
+        }
+    }
+}
+
+#[cfg(test)]
+pub use mock::MockClock;
+
+#[cfg(test)]
+mod mock {
+    use std::{cell::RefCell, ops::AddAssign, rc::Rc};
+
+    use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+
+    use super::Clock;
+
+    #[derive(Debug, Clone)]
+    pub struct MockClock {
+        now: Rc<RefCell<DateTime<Utc>>>,
+    }
+
+    impl MockClock {
+        pub fn new(secs: i64, nsecs: u32) -> Self {
+            Self {
+                now: Rc::new(RefCell::new(Utc.timestamp_opt(secs, nsecs).unwrap())),
+            }
+        }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> DateTime<Utc> {
+            *self.now.borrow()
+        }
+    }
+
+    impl AddAssign<TimeDelta> for MockClock {
+        fn add_assign(&mut self, delta: TimeDelta) {
+            *self.now.borrow_mut() += delta;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn test_monotonic_clock() {
+        let clock = MonotonicClock::new();
+        let copy = clock;
+
+        let first = clock.now();
+        let second = copy.now();
+        assert!(second >= first);
+        assert!((first - Utc::now()).abs() < TimeDelta::seconds(1));
+    }
+}
@@ -0,0 +1,146 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+
+use super::duration::MAX;
+
+/// Policy applied automatically whenever a turn starts or ends.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimeControl {
+    #[default]
+    None,
+    /// Adds a fixed increment to the bank after every move.
+    Fischer(TimeDelta),
+    /// US delay: the clock only starts counting down after a grace period.
+    Delay(TimeDelta),
+    /// Refunds the time used during the move, up to a fixed amount.
+    Bronstein(TimeDelta),
+}
+
+impl TimeControl {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TimeControl::None => "none",
+            TimeControl::Fischer(_) => "fischer",
+            TimeControl::Delay(_) => "delay",
+            TimeControl::Bronstein(_) => "bronstein",
+        }
+    }
+
+    pub fn amount(&self) -> TimeDelta {
+        match *self {
+            TimeControl::None => TimeDelta::zero(),
+            TimeControl::Fischer(amount)
+            | TimeControl::Delay(amount)
+            | TimeControl::Bronstein(amount) => amount,
+        }
+    }
+
+    pub fn with_amount(self, amount: TimeDelta) -> Self {
+        match self {
+            TimeControl::None => TimeControl::None,
+            TimeControl::Fischer(_) => TimeControl::Fischer(amount),
+            TimeControl::Delay(_) => TimeControl::Delay(amount),
+            TimeControl::Bronstein(_) => TimeControl::Bronstein(amount),
+        }
+    }
+
+    /// Grace period at the start of each turn that is not charged to the player.
+    pub fn delay(&self) -> TimeDelta {
+        match *self {
+            TimeControl::Delay(delay) => delay,
+            _ => TimeDelta::zero(),
+        }
+    }
+
+    /// Time credited back to the player's bank once a turn that used `used` ends.
+    pub fn credit(&self, used: TimeDelta) -> TimeDelta {
+        match *self {
+            TimeControl::Fischer(increment) => increment,
+            TimeControl::Bronstein(refund) => refund.min(used).max(TimeDelta::zero()),
+            TimeControl::None | TimeControl::Delay(_) => TimeDelta::zero(),
+        }
+    }
+}
+
+impl Display for TimeControl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeControl::None => write!(f, "{}", self.name()),
+            _ => write!(f, "{}:{}", self.name(), self.amount().num_seconds()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseTimeControlError(String);
+
+impl Display for ParseTimeControlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid time control: {:?}", self.0)
+    }
+}
+
+impl FromStr for TimeControl {
+    type Err = ParseTimeControlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseTimeControlError(s.to_string());
+        let (name, amount) = s.split_once(':').unwrap_or((s, "0"));
+        let amount = amount
+            .parse::<u32>()
+            .ok()
+            .map(|secs| TimeDelta::seconds(secs as i64))
+            .filter(|amount| *amount <= MAX)
+            .ok_or_else(err)?;
+        match name {
+            "none" => Ok(TimeControl::None),
+            "fischer" => Ok(TimeControl::Fischer(amount)),
+            "delay" => Ok(TimeControl::Delay(amount)),
+            "bronstein" => Ok(TimeControl::Bronstein(amount)),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_control_round_trip() {
+        let five = TimeDelta::seconds(5);
+        for control in [
+            TimeControl::None,
+            TimeControl::Fischer(five),
+            TimeControl::Delay(five),
+            TimeControl::Bronstein(five),
+        ] {
+            assert_eq!(control.to_string().parse::<TimeControl>(), Ok(control));
+        }
+        assert_eq!("fischer:5".parse(), Ok(TimeControl::Fischer(five)));
+        assert_eq!("delay".parse(), Ok(TimeControl::Delay(TimeDelta::zero())));
+        assert!("fischer:-5".parse::<TimeControl>().is_err());
+        assert!("fischer:4000000000".parse::<TimeControl>().is_err());
+        assert!("hourglass:5".parse::<TimeControl>().is_err());
+    }
+
+    #[test]
+    fn test_time_control_credit() {
+        let five = TimeDelta::seconds(5);
+        assert_eq!(TimeControl::None.credit(five), TimeDelta::zero());
+        assert_eq!(
+            TimeControl::Fischer(five).credit(TimeDelta::seconds(1)),
+            five
+        );
+        assert_eq!(TimeControl::Delay(five).credit(five), TimeDelta::zero());
+        assert_eq!(
+            TimeControl::Bronstein(five).credit(TimeDelta::seconds(3)),
+            TimeDelta::seconds(3)
+        );
+        assert_eq!(
+            TimeControl::Bronstein(five).credit(TimeDelta::seconds(8)),
+            five
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use std::fmt::Display;
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use super::snapshot::positive_millis;
+
+/// One stretch of a work-break [`Cycle`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    Work,
+    ShortBreak,
+    LongBreak,
+}
+
+impl Display for Phase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Phase::Work => write!(f, "Work"),
+            Phase::ShortBreak => write!(f, "Short break"),
+            Phase::LongBreak => write!(f, "Long break"),
+        }
+    }
+}
+
+/// Work intervals with a short break after each, and a long break instead
+/// after every `every`th, repeating for as long as the clock runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Cycle {
+    #[serde(with = "positive_millis")]
+    pub work: TimeDelta,
+    #[serde(with = "positive_millis")]
+    pub short_break: TimeDelta,
+    #[serde(with = "positive_millis")]
+    pub long_break: TimeDelta,
+    pub every: u32,
+}
+
+impl Default for Cycle {
+    fn default() -> Self {
+        Self {
+            work: TimeDelta::minutes(25),
+            short_break: TimeDelta::minutes(5),
+            long_break: TimeDelta::minutes(15),
+            every: 4,
+        }
+    }
+}
+
+impl Cycle {
+    /// The phase at `step`, counting every phase since the start from zero.
+    pub fn phase(&self, step: u32) -> Phase {
+        if step.is_multiple_of(2) {
+            Phase::Work
+        } else if self.count(step).is_multiple_of(self.every.max(1)) {
+            Phase::LongBreak
+        } else {
+            Phase::ShortBreak
+        }
+    }
+
+    /// Which work interval `step` belongs to, from one.
+    pub fn count(&self, step: u32) -> u32 {
+        step / 2 + 1
+    }
+
+    pub fn duration(&self, phase: Phase) -> TimeDelta {
+        match phase {
+            Phase::Work => self.work,
+            Phase::ShortBreak => self.short_break,
+            Phase::LongBreak => self.long_break,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_phases() {
+        let cycle = Cycle {
+            every: 2,
+            ..Default::default()
+        };
+        let phases = (0..8).map(|step| cycle.phase(step)).collect::<Vec<_>>();
+        assert_eq!(
+            phases,
+            [
+                Phase::Work,
+                Phase::ShortBreak,
+                Phase::Work,
+                Phase::LongBreak,
+                Phase::Work,
+                Phase::ShortBreak,
+                Phase::Work,
+                Phase::LongBreak,
+            ]
+        );
+        assert_eq!(cycle.count(0), 1);
+        assert_eq!(cycle.count(3), 2);
+        assert_eq!(cycle.duration(cycle.phase(3)), TimeDelta::minutes(15));
+    }
+
+    #[test]
+    fn test_cycle_rejects_empty_phases() {
+        let cycle = serde_json::from_str::<Cycle>(r#"{"work":1000,"short_break":2000}"#).unwrap();
+        assert_eq!(cycle.work, TimeDelta::seconds(1));
+        assert_eq!(cycle.long_break, TimeDelta::minutes(15));
+        for json in [r#"{"work":0}"#, r#"{"short_break":-5}"#] {
+            assert!(serde_json::from_str::<Cycle>(json).is_err());
+        }
+    }
+}
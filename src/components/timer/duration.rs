@@ -0,0 +1,242 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+/// Why a duration typed by a person could not be read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseDurationError {
+    Empty,
+    Number(String),
+    Unit(String),
+    Repeated(char),
+    /// A clock-style value with more than `H:MM:SS`.
+    Parts,
+    /// Minutes or seconds of 60 or more after a colon.
+    Range,
+    Zero,
+    /// Longer than [`MAX`].
+    TooLong,
+}
+
+/// The longest duration accepted, far beyond any game yet well clear of the
+/// range where adding to it could overflow.
+pub const MAX: TimeDelta = TimeDelta::hours(100);
+
+impl Display for ParseDurationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseDurationError::Empty => write!(f, "enter a duration, like 90, 1:30 or 1m30s"),
+            ParseDurationError::Number(s) => write!(f, "{s:?} is not a number"),
+            ParseDurationError::Unit(s) => write!(f, "unknown unit {s:?}, use h, m or s"),
+            ParseDurationError::Repeated(unit) => write!(f, "{unit:?} is given more than once"),
+            ParseDurationError::Parts => write!(f, "use M:SS or H:MM:SS"),
+            ParseDurationError::Range => write!(f, "minutes and seconds must be below 60"),
+            ParseDurationError::Zero => write!(f, "must be longer than zero"),
+            ParseDurationError::TooLong => write!(f, "must be at most {}", Limit(MAX)),
+        }
+    }
+}
+
+/// Parses `90`, `1.5m`, `1m30s`, `1h 5m`, `1:30` or `01:02:03`. A bare number
+/// is in seconds, and so is a trailing number after other units, as in `1m30`.
+pub fn parse_duration(s: &str) -> Result<TimeDelta, ParseDurationError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseDurationError::Empty);
+    }
+    if s.contains(':') {
+        parse_clock(s)
+    } else {
+        parse_units(s)
+    }
+}
+
+fn number(s: &str) -> Result<f64, ParseDurationError> {
+    let valid = s.chars().all(|c| c.is_ascii_digit() || c == '.');
+    s.parse::<f64>()
+        .ok()
+        .filter(|_| valid)
+        .ok_or_else(|| ParseDurationError::Number(s.to_string()))
+}
+
+fn seconds(secs: f64) -> Result<TimeDelta, ParseDurationError> {
+    if secs > MAX.num_seconds() as f64 {
+        return Err(ParseDurationError::TooLong);
+    }
+    Ok(TimeDelta::milliseconds((secs * 1000.0).round() as i64))
+}
+
+fn parse_clock(s: &str) -> Result<TimeDelta, ParseDurationError> {
+    let parts = s.split(':').map(str::trim).collect::<Vec<_>>();
+    if parts.len() > 3 {
+        return Err(ParseDurationError::Parts);
+    }
+    let mut total = 0.0;
+    for (index, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            return Err(ParseDurationError::Parts);
+        }
+        let value = number(part)?;
+        if index > 0 && value >= 60.0 {
+            return Err(ParseDurationError::Range);
+        }
+        total = total * 60.0 + value;
+    }
+    seconds(total)
+}
+
+fn parse_units(s: &str) -> Result<TimeDelta, ParseDurationError> {
+    let mut total = 0.0;
+    let mut seen = Vec::new();
+    let mut rest = s;
+    while !rest.is_empty() {
+        let split = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let (digits, tail) = rest.split_at(split);
+        if digits.is_empty() {
+            return Err(ParseDurationError::Number(rest.to_string()));
+        }
+        let value = number(digits)?;
+        let tail = tail.trim_start();
+        let split = tail
+            .find(|c: char| !c.is_ascii_alphabetic())
+            .unwrap_or(tail.len());
+        let (unit, tail) = tail.split_at(split);
+        let unit = match unit.to_ascii_lowercase().as_str() {
+            "h" => 'h',
+            "m" => 'm',
+            "s" | "" => 's',
+            _ => return Err(ParseDurationError::Unit(unit.to_string())),
+        };
+        if seen.contains(&unit) {
+            return Err(ParseDurationError::Repeated(unit));
+        }
+        seen.push(unit);
+        total += value
+            * match unit {
+                'h' => 3600.0,
+                'm' => 60.0,
+                _ => 1.0,
+            };
+        rest = tail.trim_start();
+    }
+    seconds(total)
+}
+
+/// A time limit as given in the lobby or the `time_limit` query argument.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Limit(pub TimeDelta);
+
+impl Default for Limit {
+    fn default() -> Self {
+        Self(TimeDelta::seconds(90)) // TODO: make configurable
+    }
+}
+
+impl FromStr for Limit {
+    type Err = ParseDurationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_duration(s)? {
+            delta if delta <= TimeDelta::zero() => Err(ParseDurationError::Zero),
+            delta => Ok(Self(delta)),
+        }
+    }
+}
+
+impl From<Limit> for String {
+    fn from(limit: Limit) -> Self {
+        limit.to_string()
+    }
+}
+
+impl TryFrom<String> for Limit {
+    type Error = ParseDurationError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl Display for Limit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let millis = self.0.num_milliseconds();
+        let (hours, minutes) = (millis / 3_600_000, millis / 60_000 % 60);
+        let secs = millis % 60_000;
+        if hours > 0 {
+            write!(f, "{hours}h")?;
+        }
+        if minutes > 0 {
+            write!(f, "{minutes}m")?;
+        }
+        match secs {
+            0 if millis > 0 => Ok(()),
+            _ if secs % 1000 == 0 => write!(f, "{}s", secs / 1000),
+            _ => write!(f, "{}s", secs as f64 / 1000.0),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: i64) -> Result<TimeDelta, ParseDurationError> {
+        Ok(TimeDelta::seconds(secs))
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), secs(90));
+        assert_eq!(parse_duration(" 1:30 "), secs(90));
+        assert_eq!(parse_duration("1m30s"), secs(90));
+        assert_eq!(parse_duration("1m30"), secs(90));
+        assert_eq!(parse_duration("1.5m"), secs(90));
+        assert_eq!(parse_duration("1h 5M"), secs(3900));
+        assert_eq!(parse_duration("01:02:03"), secs(3723));
+        assert_eq!(parse_duration("2.5"), Ok(TimeDelta::milliseconds(2500)));
+        assert_eq!(parse_duration("0"), secs(0));
+    }
+
+    #[test]
+    fn test_parse_duration_errors() {
+        use ParseDurationError::*;
+        assert_eq!(parse_duration(" "), Err(Empty));
+        assert_eq!(parse_duration("abc"), Err(Number("abc".to_string())));
+        assert_eq!(parse_duration("1x"), Err(Unit("x".to_string())));
+        assert_eq!(parse_duration("1min"), Err(Unit("min".to_string())));
+        assert_eq!(parse_duration("1m2m"), Err(Repeated('m')));
+        assert_eq!(parse_duration("1:2:3:4"), Err(Parts));
+        assert_eq!(parse_duration("1:75"), Err(Range));
+        assert_eq!(parse_duration("1::30"), Err(Parts));
+        assert_eq!(parse_duration("1.2.3"), Err(Number("1.2.3".to_string())));
+        assert_eq!(parse_duration("-5"), Err(Number("-5".to_string())));
+        assert_eq!(parse_duration("100h"), Ok(MAX));
+        assert_eq!(parse_duration("100h 1s"), Err(TooLong));
+        assert_eq!(parse_duration("9999999999999h"), Err(TooLong));
+        assert_eq!(parse_duration("999999:00:00"), Err(TooLong));
+        assert_eq!(parse_duration(&"9".repeat(400)), Err(TooLong));
+    }
+
+    #[test]
+    fn test_limit_round_trip() {
+        for text in ["90", "2m", "1:30", "1h", "01:02:03", "0.5s", "1m0.25s"] {
+            let limit = text.parse::<Limit>().unwrap();
+            assert_eq!(limit.to_string().parse(), Ok(limit));
+        }
+        assert_eq!(Limit(TimeDelta::seconds(90)).to_string(), "1m30s");
+        assert_eq!(Limit(TimeDelta::seconds(3600)).to_string(), "1h");
+        assert_eq!("0:00".parse::<Limit>(), Err(ParseDurationError::Zero));
+
+        let json = serde_json::to_string(&Limit(TimeDelta::seconds(90))).unwrap();
+        assert_eq!(json, r#""1m30s""#);
+        assert_eq!(
+            serde_json::from_str::<Limit>(&json).unwrap(),
+            Limit(TimeDelta::seconds(90))
+        );
+        assert!(serde_json::from_str::<Limit>(r#""soon""#).is_err());
+    }
+}
@@ -0,0 +1,38 @@
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use super::thresholds::Level;
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TimerState {
+    /// Not started since it was created or last lapped.
+    #[default]
+    Idle,
+    Running,
+    Paused,
+    /// Time is up. Stays latched until a lap or an extension adds time back.
+    Expired,
+}
+
+/// Discrete transitions emitted by a timer, each exactly once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TimerEvent {
+    Started,
+    Paused,
+    Resumed,
+    Expired,
+    Lapped(TimeDelta),
+    Extended(TimeDelta),
+    /// The time left dropped to a warning threshold.
+    Warning(Level),
+    /// Whole seconds left, once a warning threshold has been reached.
+    Second(u32),
+}
+
+/// A [`TimerEvent`] of the stopwatch owned by the player at `player`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlayerEvent {
+    pub player: usize,
+    pub event: TimerEvent,
+}
@@ -0,0 +1,269 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+/// How negative (overtime) durations are marked.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sign {
+    /// Overtime looks just like time remaining.
+    #[default]
+    Hidden,
+    /// `-00:05`: the time left has gone negative.
+    Minus,
+    /// `+00:05`: the time spent past the limit.
+    Plus,
+}
+
+/// Which way the part of a duration that does not fill a whole digit goes.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// Towards zero, so `00:00` shows for the whole final second.
+    #[default]
+    Floor,
+    /// Away from zero, so `00:00` only shows once time is up.
+    Ceil,
+}
+
+/// Options for showing a duration on a clock face.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", try_from = "String")]
+pub struct Format {
+    /// `H:MM:SS` instead of `MM:SS`.
+    pub hours: bool,
+    /// Adds tenths of a second to durations shorter than this.
+    pub tenths: Option<TimeDelta>,
+    pub sign: Sign,
+    pub rounding: Rounding,
+}
+
+impl Format {
+    pub fn apply(self, delta: TimeDelta) -> Formatted {
+        Formatted(delta, self)
+    }
+}
+
+/// A duration shown in a given [`Format`].
+pub struct Formatted(TimeDelta, Format);
+
+impl Display for Formatted {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Formatted(delta, format) = *self;
+        let tenths = format.tenths.is_some_and(|below| delta.abs() < below);
+        let unit = if tenths { 100 } else { 1000 };
+        let millis = delta.num_milliseconds().abs();
+        let units = match format.rounding {
+            Rounding::Floor => millis / unit,
+            Rounding::Ceil => (millis + unit - 1) / unit,
+        };
+
+        if delta < TimeDelta::zero() && units > 0 {
+            match format.sign {
+                Sign::Hidden => {}
+                Sign::Minus => write!(f, "-")?,
+                Sign::Plus => write!(f, "+")?,
+            }
+        }
+        let seconds = if tenths { units / 10 } else { units };
+        if format.hours {
+            write!(
+                f,
+                "{}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            )?;
+        } else {
+            write!(f, "{:02}:{:02}", seconds / 60, seconds % 60)?;
+        }
+        if tenths {
+            write!(f, ".{}", units % 10)?;
+        }
+        Ok(())
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", if self.hours { "hms" } else { "ms" })?;
+        if let Some(below) = self.tenths {
+            write!(f, ",tenths:{}", below.num_seconds())?;
+        }
+        match self.sign {
+            Sign::Hidden => {}
+            Sign::Minus => write!(f, ",minus")?,
+            Sign::Plus => write!(f, ",plus")?,
+        }
+        if self.rounding == Rounding::Ceil {
+            write!(f, ",ceil")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseFormatError(String);
+
+impl Display for ParseFormatError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid duration format: {:?}", self.0)
+    }
+}
+
+impl FromStr for Format {
+    type Err = ParseFormatError;
+
+    /// Parses a comma-separated list of options, e.g. `hms,tenths:10,plus,ceil`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseFormatError(s.to_string());
+        let mut format = Format::default();
+        for option in s.split(',') {
+            match option.split_once(':') {
+                Some(("tenths", secs)) => {
+                    let secs = secs
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|&n| n > 0)
+                        .ok_or_else(err)?;
+                    format.tenths = Some(TimeDelta::seconds(secs as i64));
+                }
+                Some(_) => return Err(err()),
+                None => match option {
+                    "ms" => format.hours = false,
+                    "hms" => format.hours = true,
+                    "minus" => format.sign = Sign::Minus,
+                    "plus" => format.sign = Sign::Plus,
+                    "floor" => format.rounding = Rounding::Floor,
+                    "ceil" => format.rounding = Rounding::Ceil,
+                    _ => return Err(err()),
+                },
+            }
+        }
+        Ok(format)
+    }
+}
+
+impl From<Format> for String {
+    fn from(format: Format) -> Self {
+        format.to_string()
+    }
+}
+
+impl TryFrom<String> for Format {
+    type Error = ParseFormatError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn show(format: Format, millis: i64) -> String {
+        format.apply(TimeDelta::milliseconds(millis)).to_string()
+    }
+
+    #[test]
+    fn test_default_format() {
+        let format = Format::default();
+        assert_eq!(show(format, 0), "00:00");
+        assert_eq!(show(format, 90_000), "01:30");
+        assert_eq!(show(format, 7_425_000), "123:45");
+        // the final second still reads zero, and overtime looks like time left
+        assert_eq!(show(format, 999), "00:00");
+        assert_eq!(show(format, -5_500), "00:05");
+    }
+
+    #[test]
+    fn test_hours() {
+        let format = Format {
+            hours: true,
+            ..Default::default()
+        };
+        assert_eq!(show(format, 59_000), "0:00:59");
+        assert_eq!(show(format, 3_723_000), "1:02:03");
+        assert_eq!(show(format, 7_425_000), "2:03:45");
+    }
+
+    #[test]
+    fn test_tenths() {
+        let format = Format {
+            tenths: Some(TimeDelta::seconds(10)),
+            ..Default::default()
+        };
+        assert_eq!(show(format, 10_000), "00:10");
+        assert_eq!(show(format, 9_999), "00:09.9");
+        assert_eq!(show(format, 1_250), "00:01.2");
+        assert_eq!(show(format, -300), "00:00.3");
+    }
+
+    #[test]
+    fn test_sign() {
+        let minus = Format {
+            sign: Sign::Minus,
+            ..Default::default()
+        };
+        let plus = Format {
+            sign: Sign::Plus,
+            ..Default::default()
+        };
+        assert_eq!(show(minus, 5_000), "00:05");
+        assert_eq!(show(minus, -5_000), "-00:05");
+        assert_eq!(show(plus, -65_000), "+01:05");
+        // nothing to mark until a whole digit has passed
+        assert_eq!(show(plus, -999), "00:00");
+    }
+
+    #[test]
+    fn test_rounding() {
+        let ceil = Format {
+            rounding: Rounding::Ceil,
+            sign: Sign::Plus,
+            ..Default::default()
+        };
+        assert_eq!(show(ceil, 4_000), "00:04");
+        assert_eq!(show(ceil, 3_001), "00:04");
+        assert_eq!(show(ceil, 1), "00:01");
+        assert_eq!(show(ceil, 0), "00:00");
+        assert_eq!(show(ceil, -1), "+00:01");
+
+        let tenths = Format {
+            tenths: Some(TimeDelta::seconds(5)),
+            ..ceil
+        };
+        assert_eq!(show(tenths, 1_201), "00:01.3");
+    }
+
+    #[test]
+    fn test_format_round_trip() {
+        for format in [
+            Format::default(),
+            Format {
+                hours: true,
+                tenths: Some(TimeDelta::seconds(10)),
+                sign: Sign::Plus,
+                rounding: Rounding::Ceil,
+            },
+            Format {
+                sign: Sign::Minus,
+                ..Default::default()
+            },
+        ] {
+            assert_eq!(format.to_string().parse::<Format>(), Ok(format));
+        }
+        assert_eq!(Format::default().to_string(), "ms");
+        assert_eq!(
+            "hms,ceil".parse(),
+            Ok(Format {
+                hours: true,
+                rounding: Rounding::Ceil,
+                ..Default::default()
+            })
+        );
+        assert!("tenths:0".parse::<Format>().is_err());
+        assert!("ms,bold".parse::<Format>().is_err());
+        assert!("".parse::<Format>().is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::snapshot::millis;
+
+/// A finished turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lap {
+    pub player: usize,
+    #[serde(with = "millis")]
+    pub duration: TimeDelta,
+    /// Part of the turn spent past the player's limit.
+    #[serde(with = "millis", default)]
+    pub overrun: TimeDelta,
+    /// Time added by hand during the turn.
+    #[serde(with = "millis", default)]
+    pub extension: TimeDelta,
+    pub finished: DateTime<Utc>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct History(Vec<Lap>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stats {
+    pub turns: usize,
+    pub min: TimeDelta,
+    pub max: TimeDelta,
+    pub mean: TimeDelta,
+    pub median: TimeDelta,
+    pub overrun: TimeDelta,
+}
+
+impl History {
+    pub fn push(&mut self, lap: Lap) {
+        self.0.push(lap);
+    }
+
+    pub fn laps(&self) -> &[Lap] {
+        &self.0
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        Stats::of(self.0.iter())
+    }
+
+    pub fn stats_for(&self, player: usize) -> Option<Stats> {
+        Stats::of(self.0.iter().filter(|lap| lap.player == player))
+    }
+}
+
+impl Stats {
+    fn of<'a>(laps: impl Iterator<Item = &'a Lap>) -> Option<Self> {
+        let laps: Vec<&Lap> = laps.collect();
+        if laps.is_empty() {
+            return None;
+        }
+        let mut durations: Vec<TimeDelta> = laps.iter().map(|lap| lap.duration).collect();
+        durations.sort();
+
+        let turns = durations.len();
+        let total: TimeDelta = durations.iter().sum();
+        let middle = turns / 2;
+        let median = if turns.is_multiple_of(2) {
+            (durations[middle - 1] + durations[middle]) / 2
+        } else {
+            durations[middle]
+        };
+
+        Some(Self {
+            turns,
+            min: durations[0],
+            max: durations[turns - 1],
+            mean: total / turns as i32,
+            median,
+            overrun: laps.iter().map(|lap| lap.overrun).sum(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn lap(player: usize, secs: i64, overrun: i64) -> Lap {
+        Lap {
+            player,
+            duration: TimeDelta::seconds(secs),
+            overrun: TimeDelta::seconds(overrun),
+            extension: TimeDelta::zero(),
+            finished: Utc.timestamp_opt(0, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_empty_history_has_no_stats() {
+        assert_eq!(History::default().stats(), None);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut history = History::default();
+        for (player, secs, overrun) in [(0, 10, 0), (1, 40, 10), (0, 20, 0), (1, 30, 0)] {
+            history.push(lap(player, secs, overrun));
+        }
+
+        let stats = history.stats().unwrap();
+        assert_eq!(stats.turns, 4);
+        assert_eq!(stats.min, TimeDelta::seconds(10));
+        assert_eq!(stats.max, TimeDelta::seconds(40));
+        assert_eq!(stats.mean, TimeDelta::seconds(25));
+        assert_eq!(stats.median, TimeDelta::seconds(25));
+        assert_eq!(stats.overrun, TimeDelta::seconds(10));
+
+        let first = history.stats_for(0).unwrap();
+        assert_eq!(first.turns, 2);
+        assert_eq!(first.mean, TimeDelta::seconds(15));
+
+        history.push(lap(0, 90, 0));
+        assert_eq!(history.stats().unwrap().median, TimeDelta::seconds(30));
+        assert_eq!(history.stats_for(2), None);
+    }
+}
@@ -0,0 +1,365 @@
+use chrono::TimeDelta;
+use dioxus::prelude::*;
+use gloo_timers::callback::Interval;
+
+use super::Roster;
+
+mod adjust;
+pub use adjust::{Adjustments, Budget};
+
+mod alerts;
+use alerts::Alerts;
+
+mod bank;
+use bank::Bank;
+pub use bank::{Mode, Rules};
+
+mod clock;
+use clock::MonotonicClock;
+
+mod cycle;
+pub use cycle::Cycle;
+
+mod control;
+pub use control::TimeControl;
+
+mod duration;
+pub use duration::{Limit, MAX as MAX_DURATION};
+
+mod format;
+pub use format::{Format, Rounding, Sign};
+
+mod history;
+pub use history::{History, Stats};
+
+mod event;
+use event::TimerState;
+pub use event::{PlayerEvent, TimerEvent};
+
+mod sequence;
+pub use sequence::{Advance, Segment, Sequence};
+
+mod snapshot;
+pub use snapshot::BankSnapshot;
+
+mod overtime;
+pub use overtime::Overtime;
+use overtime::Standing;
+
+mod thresholds;
+pub use thresholds::Level;
+pub use thresholds::Thresholds;
+
+mod stopwatch;
+pub use stopwatch::Countdown;
+
+mod undo;
+use undo::Step;
+
+const TICK_MS: u32 = 1000 / 25;
+
+/// How long the toast offering to undo the latest command stays up.
+const TOAST: TimeDelta = TimeDelta::seconds(5);
+
+pub type TimerBank = Bank<MonotonicClock>;
+
+/// Creates the bank shared by the [`Timer`] and anything that reports on it.
+pub fn use_bank(rules: Rules, players: usize) -> Signal<TimerBank> {
+    use_signal(move || new_bank(rules, players))
+}
+
+pub fn new_bank(rules: Rules, players: usize) -> TimerBank {
+    Bank::new(MonotonicClock::new(), rules, players)
+}
+
+pub fn restore_bank(snapshot: BankSnapshot) -> Option<TimerBank> {
+    Bank::restore(MonotonicClock::new(), snapshot)
+}
+
+#[component]
+pub fn Timer(
+    bank: Signal<TimerBank>,
+    players: Roster,
+    format: Format,
+    thresholds: Thresholds,
+    adjustments: Adjustments,
+    /// How many final seconds to report, whatever the thresholds.
+    #[props(default)]
+    ticks: u32,
+    on_event: Option<EventHandler<PlayerEvent>>,
+) -> Element {
+    let current = bank.read();
+    let active = current.active_index();
+    let progress = current.progress().unwrap_or_else(|| {
+        // without a limit the bar sweeps round once a minute, like a second hand
+        let millis = current.face(active).num_milliseconds();
+        (millis % 60_000) as f32 / 60_000.0
+    });
+    let level = |index: usize| match (current.standing(index), current.countdown(index)) {
+        (Standing::Lost, _) => Level::Overtime,
+        (_, Some(countdown)) => thresholds.level(countdown.0),
+        (_, None) => Level::Normal,
+    };
+    let time_remain = format.apply(current.face(active));
+    let standing = current.standing(active);
+    let delay = (current.active().delay_remaining().num_milliseconds() + 999) / 1000;
+    let toast = current.last_step(TOAST);
+    let allowance = current.allowance();
+
+    let mut alerts = use_signal(|| Alerts::new(thresholds, ticks));
+    use_effect(use_reactive!(
+        |thresholds, ticks| alerts.set(Alerts::new(thresholds, ticks))
+    ));
+
+    let mut dispatch = move || {
+        let mut events = bank.write().take_events();
+        let current = bank.read();
+        let player = current.active_index();
+        if let (true, Some(countdown)) = (current.running(), current.countdown(player)) {
+            let remaining = countdown.0;
+            let warnings = alerts.write().update(player, remaining);
+            events.extend(
+                warnings
+                    .into_iter()
+                    .map(|event| PlayerEvent { player, event }),
+            );
+        }
+        drop(current);
+        if let Some(handler) = on_event {
+            for event in events {
+                handler.call(event);
+            }
+        }
+    };
+
+    use_effect(move || {
+        let interval = Interval::new(TICK_MS, move || {
+            bank.write().tick(); // also triggers a re-render
+            dispatch();
+        });
+        interval.forget();
+    });
+
+    rsx! {
+        div { class: "flex flex-col space-y-2 mt-8 w-full max-w-2xl mx-auto",
+            if let Some((phase, count)) = current.phase() {
+                h2 { class: "text-2xl font-bold", "{phase} \u{00B7} cycle {count}" }
+            } else if let Some((sequence, step)) = current.sequence() {
+                h2 { class: "text-2xl font-bold",
+                    match current.segment() {
+                        Some(segment) => format!("{} \u{00B7} {} of {}", segment.name, step + 1, sequence.steps()),
+                        None => "Sequence finished".to_string(),
+                    }
+                }
+            } else if players.is_empty() {
+                h2 { class: "text-2xl font-bold", "Timer" }
+            } else {
+                h2 { class: "text-2xl font-bold", "{players.name(active)}'s turn" }
+            }
+            if current.mode() == Mode::Hourglass {
+                div { class: "grid grid-cols-2 gap-2 w-full m-1",
+                    for index in 0..current.stopwatches().len() {
+                        button {
+                            key: "{index}",
+                            class: if index == active { "relative h-48 overflow-hidden rounded bg-gray-800 ring-4 ring-blue-500" } else { "relative h-48 overflow-hidden rounded bg-gray-800 opacity-60" },
+                            title: if index != active { "Switch to this player" },
+                            onclick: move |_| {
+                                if index == active {
+                                    bank.write().lap();
+                                } else {
+                                    bank.write().select(index);
+                                }
+                                dispatch();
+                            },
+                            div {
+                                class: sand_class(level(index)),
+                                style: if level(index) == Level::Overtime { "height: 100%".to_string() } else { format!("height: {}%", current.sand(index) * 100f32) },
+                            }
+                            div { class: "absolute inset-0 flex flex-col items-center justify-center text-shadow-md/50",
+                                span { class: "text-lg", "{players.name(index)}" }
+                                span { class: "text-4xl font-bold font-mono", "{format.apply(current.face(index))}" }
+                            }
+                        }
+                    }
+                }
+            } else {
+                div { class: "relative w-full bg-gray-800 h-15 m-1 overflow-hidden rounded",
+                    div {
+                        class: bar_class(level(active)),
+                        style: "width: {progress * 100f32}%",
+                    }
+                    span { class: "absolute inset-0 flex items-center justify-center text-2xl font-bold text-shadow-md/50",
+                        "{time_remain}"
+                    }
+                    if delay > 0 {
+                        span { class: "absolute right-2 inset-y-0 flex items-center text-sm text-slate-300",
+                            "delay {delay}s"
+                        }
+                    }
+                }
+                if let Some(next) = current.next_phase() {
+                    p { class: "m-1 text-slate-300", "Next: {next}" }
+                } else if let Some(next) = current.next_segment() {
+                    p { class: "m-1 text-slate-300", "Next: {next.name}" }
+                }
+                match standing {
+                    Standing::Main if current.state(active) == TimerState::Expired => rsx! {
+                        p { class: "m-1 font-bold text-red-500", "Time's up" }
+                    },
+                    Standing::Main => rsx! {},
+                    Standing::Lost => rsx! {
+                        p { class: "m-1 font-bold text-red-500", "\u{2691} {players.name(active)} lost on time" }
+                    },
+                    _ => rsx! {
+                        p { class: "m-1 text-slate-300", "{describe(standing)}" }
+                    },
+                }
+                if current.stopwatches().len() > 1 {
+                    ul { class: "flex flex-wrap gap-2 m-1 text-sm",
+                        for index in (0..current.stopwatches().len()).filter(|&i| i != active) {
+                            li {
+                                key: "{index}",
+                                button {
+                                    class: "px-2 py-1 rounded bg-gray-800 hover:bg-gray-700 text-slate-400",
+                                    title: "Switch to this player",
+                                    onclick: move |_| {
+                                        bank.write().select(index);
+                                        dispatch();
+                                    },
+                                    "{players.name(index)} "
+                                    if current.standing(index) == Standing::Lost {
+                                        span { class: "text-red-500", "\u{2691}" }
+                                    } else {
+                                        span { class: "font-mono text-slate-200", "{format.apply(current.face(index))}" }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            div { class: "columns-2 w-full",
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 w-full text-white rounded h-15 m-1 text-2xl font-bold",
+                    onclick: move |_| {
+                        bank.write().toggle();
+                        dispatch();
+                    },
+                    if current.running() {
+                        "\u{23F8}"
+                    } else {
+                        "\u{23F5}"
+                    }
+                }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 w-full text-white rounded h-15 m-1 text-2xl font-bold",
+                    onclick: move |_| {
+                        bank.write().lap();
+                        dispatch();
+                    },
+                    "\u{23ED}"
+                }
+            }
+            if !adjustments.0.is_empty() {
+                div { class: "flex gap-2 w-full m-1",
+                    for (index , delta) in adjustments.0.iter().copied().enumerate() {
+                        button {
+                            key: "{index}",
+                            class: "flex-1 bg-gray-700 hover:bg-gray-600 text-white rounded h-12 text-xl font-bold disabled:opacity-50 disabled:cursor-not-allowed",
+                            disabled: !allowance.allows(delta),
+                            onclick: move |_| {
+                                let result = bank.write().extend_time(delta);
+                                if let Err(err) = result {
+                                    warn!("{err}");
+                                }
+                                dispatch();
+                            },
+                            "{adjust::label(delta)}"
+                        }
+                    }
+                }
+                if let Some(left) = describe_allowance(allowance) {
+                    p { class: if allowance.allows(TimeDelta::seconds(1)) { "m-1 text-sm text-slate-400" } else { "m-1 text-sm text-red-400" },
+                        "{left}"
+                    }
+                }
+            }
+            if let Some(step) = toast {
+                div { class: "fixed bottom-4 left-1/2 -translate-x-1/2 z-10 flex items-center gap-3 px-4 py-2 rounded bg-slate-800 border border-slate-700 shadow-lg",
+                    span { class: "text-slate-300",
+                        match step {
+                            Step::Done(command) | Step::Redone(command) => command.to_string(),
+                            Step::Undone(command) => format!("Undid \u{201C}{command}\u{201D}"),
+                        }
+                    }
+                    if current.can_undo() {
+                        button {
+                            class: "font-bold text-blue-400 hover:text-blue-300",
+                            onclick: move |_| {
+                                bank.write().undo();
+                                dispatch();
+                            },
+                            "Undo"
+                        }
+                    }
+                    if current.can_redo() {
+                        button {
+                            class: "font-bold text-blue-400 hover:text-blue-300",
+                            onclick: move |_| {
+                                bank.write().redo();
+                                dispatch();
+                            },
+                            "Redo"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Pulsing is left to `motion-safe`, so it stops for anyone who prefers reduced motion.
+fn bar_class(level: Level) -> &'static str {
+    match level {
+        Level::Normal => "h-full bg-gradient-to-b from-blue-400 via-blue-600 to-slate-800",
+        Level::Warning => "h-full bg-gradient-to-b from-yellow-300 via-yellow-500 to-slate-800",
+        Level::Critical => "h-full bg-gradient-to-b from-red-400 via-red-600 to-slate-800",
+        Level::Overtime => "h-full bg-red-700 motion-safe:animate-pulse",
+    }
+}
+
+fn sand_class(level: Level) -> &'static str {
+    match level {
+        Level::Normal => "absolute inset-x-0 bottom-0 bg-gradient-to-t from-amber-600 to-amber-400",
+        Level::Warning => {
+            "absolute inset-x-0 bottom-0 bg-gradient-to-t from-yellow-500 to-yellow-300"
+        }
+        Level::Critical => "absolute inset-x-0 bottom-0 bg-gradient-to-t from-red-600 to-red-400",
+        Level::Overtime => "absolute inset-0 bg-red-900 motion-safe:animate-pulse",
+    }
+}
+
+/// The rest of a limited extension budget, for showing under the buttons.
+fn describe_allowance(allowance: adjust::Allowance) -> Option<String> {
+    let count = allowance.count.map(|count| match count {
+        0 => "No extensions left".to_string(),
+        1 => "1 extension left".to_string(),
+        count => format!("{count} extensions left"),
+    });
+    let total = allowance.total.map(|total| match total {
+        total if total <= TimeDelta::zero() => "No extra time left".to_string(),
+        total => format!("{} of extra time left", Limit(total)),
+    });
+    let parts = [count, total].into_iter().flatten().collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join(" \u{00B7} "))
+}
+
+fn describe(standing: Standing) -> String {
+    match standing {
+        Standing::ByoYomi { periods: 1, .. } => "Byo-yomi \u{00B7} last period".to_string(),
+        Standing::ByoYomi { periods, .. } => format!("Byo-yomi \u{00B7} {periods} periods left"),
+        Standing::Canadian { moves: 1, .. } => "Overtime \u{00B7} 1 move left".to_string(),
+        Standing::Canadian { moves, .. } => format!("Overtime \u{00B7} {moves} moves left"),
+        Standing::Main | Standing::Lost => String::new(),
+    }
+}
@@ -0,0 +1,308 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+
+use super::duration::MAX;
+
+/// What happens once a player's main time runs out.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Overtime {
+    /// The clock keeps counting into negative time.
+    #[default]
+    None,
+    /// Japanese byo-yomi: `periods` periods of `period` each. A period is only
+    /// used up if it expires before the move is made.
+    ByoYomi { periods: u32, period: TimeDelta },
+    /// Canadian overtime: `moves` moves must be made within `period`.
+    Canadian { moves: u32, period: TimeDelta },
+}
+
+/// Where a player stands relative to their main time and overtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Standing {
+    Main,
+    ByoYomi { remaining: TimeDelta, periods: u32 },
+    Canadian { remaining: TimeDelta, moves: u32 },
+    Lost,
+}
+
+impl Overtime {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Overtime::None => "none",
+            Overtime::ByoYomi { .. } => "byoyomi",
+            Overtime::Canadian { .. } => "canadian",
+        }
+    }
+
+    pub fn count(&self) -> u32 {
+        match *self {
+            Overtime::None => 0,
+            Overtime::ByoYomi { periods, .. } => periods,
+            Overtime::Canadian { moves, .. } => moves,
+        }
+    }
+
+    pub fn period(&self) -> TimeDelta {
+        match *self {
+            Overtime::None => TimeDelta::zero(),
+            Overtime::ByoYomi { period, .. } | Overtime::Canadian { period, .. } => period,
+        }
+    }
+
+    pub fn with_count(self, count: u32) -> Self {
+        match self {
+            Overtime::None => Overtime::None,
+            Overtime::ByoYomi { period, .. } => Overtime::ByoYomi {
+                periods: count,
+                period,
+            },
+            Overtime::Canadian { period, .. } => Overtime::Canadian {
+                moves: count,
+                period,
+            },
+        }
+    }
+
+    pub fn with_period(self, period: TimeDelta) -> Self {
+        match self {
+            Overtime::None => Overtime::None,
+            Overtime::ByoYomi { periods, .. } => Overtime::ByoYomi { periods, period },
+            Overtime::Canadian { moves, .. } => Overtime::Canadian { moves, period },
+        }
+    }
+
+    /// Standing of a player with `remaining` main time, who has made `moves`
+    /// moves since entering Canadian overtime.
+    pub fn standing(&self, remaining: TimeDelta, moves: Option<u32>) -> Standing {
+        let over = -remaining;
+        match *self {
+            Overtime::None => Standing::Main,
+            _ if over < TimeDelta::zero() && moves.is_none() => Standing::Main,
+            Overtime::ByoYomi { periods, period } => {
+                let used = periods_used(over, period);
+                if used >= periods as i64 {
+                    Standing::Lost
+                } else {
+                    Standing::ByoYomi {
+                        remaining: period * (used as i32 + 1) - over,
+                        periods: periods - used as u32,
+                    }
+                }
+            }
+            Overtime::Canadian {
+                moves: total,
+                period,
+            } => {
+                if over >= period {
+                    Standing::Lost
+                } else {
+                    Standing::Canadian {
+                        remaining: period - over,
+                        moves: total.saturating_sub(moves.unwrap_or(0)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Settles a finished turn, returning the time to give back to the player
+    /// and the updated Canadian move count.
+    pub fn end_turn(&self, remaining: TimeDelta, moves: Option<u32>) -> (TimeDelta, Option<u32>) {
+        let over = -remaining;
+        match (*self, self.standing(remaining, moves)) {
+            (Overtime::ByoYomi { period, .. }, Standing::ByoYomi { .. }) => {
+                // the period the move was made in starts over
+                let used = periods_used(over, period);
+                (over - period * used as i32, None)
+            }
+            (Overtime::Canadian { moves: total, .. }, Standing::Canadian { .. }) => {
+                let made = moves.unwrap_or(0) + 1;
+                if made >= total {
+                    // all moves made in time, so a fresh period starts
+                    (over, Some(0))
+                } else {
+                    (TimeDelta::zero(), Some(made))
+                }
+            }
+            _ => (TimeDelta::zero(), moves),
+        }
+    }
+}
+
+fn periods_used(over: TimeDelta, period: TimeDelta) -> i64 {
+    if period <= TimeDelta::zero() {
+        return i64::MAX;
+    }
+    over.num_milliseconds() / period.num_milliseconds()
+}
+
+impl Display for Overtime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Overtime::None => write!(f, "{}", self.name()),
+            _ => write!(
+                f,
+                "{}:{}:{}",
+                self.name(),
+                self.count(),
+                self.period().num_seconds()
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOvertimeError(String);
+
+impl Display for ParseOvertimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid overtime: {:?}", self.0)
+    }
+}
+
+impl FromStr for Overtime {
+    type Err = ParseOvertimeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseOvertimeError(s.to_string());
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default();
+        if name == "none" {
+            return Ok(Overtime::None);
+        }
+        let count = parts
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+            .filter(|&n| n > 0)
+            .ok_or_else(err)?;
+        let period = parts
+            .next()
+            .and_then(|n| n.parse::<u32>().ok())
+            .filter(|&n| n > 0)
+            .map(|secs| TimeDelta::seconds(secs as i64))
+            .filter(|period| *period <= MAX)
+            .ok_or_else(err)?;
+        match name {
+            "byoyomi" => Ok(Overtime::ByoYomi {
+                periods: count,
+                period,
+            }),
+            "canadian" => Ok(Overtime::Canadian {
+                moves: count,
+                period,
+            }),
+            _ => Err(err()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(secs: i64) -> TimeDelta {
+        TimeDelta::seconds(secs)
+    }
+
+    #[test]
+    fn test_overtime_round_trip() {
+        for overtime in [
+            Overtime::None,
+            Overtime::ByoYomi {
+                periods: 5,
+                period: secs(30),
+            },
+            Overtime::Canadian {
+                moves: 10,
+                period: secs(300),
+            },
+        ] {
+            assert_eq!(overtime.to_string().parse::<Overtime>(), Ok(overtime));
+        }
+        assert!("byoyomi:0:30".parse::<Overtime>().is_err());
+        assert!("byoyomi:3:4000000000".parse::<Overtime>().is_err());
+        assert!("canadian:10".parse::<Overtime>().is_err());
+        assert!("hourglass:1:1".parse::<Overtime>().is_err());
+    }
+
+    #[test]
+    fn test_byo_yomi_standing() {
+        let overtime = Overtime::ByoYomi {
+            periods: 3,
+            period: secs(10),
+        };
+        assert_eq!(overtime.standing(secs(1), None), Standing::Main);
+        assert_eq!(
+            overtime.standing(secs(0), None),
+            Standing::ByoYomi {
+                remaining: secs(10),
+                periods: 3
+            }
+        );
+        assert_eq!(
+            overtime.standing(secs(-14), None),
+            Standing::ByoYomi {
+                remaining: secs(6),
+                periods: 2
+            }
+        );
+        assert_eq!(overtime.standing(secs(-30), None), Standing::Lost);
+    }
+
+    #[test]
+    fn test_byo_yomi_end_turn() {
+        let overtime = Overtime::ByoYomi {
+            periods: 3,
+            period: secs(10),
+        };
+        // moves made in main time are not affected
+        assert_eq!(overtime.end_turn(secs(5), None), (TimeDelta::zero(), None));
+        // the partially used period is given back, the expired one is not
+        assert_eq!(overtime.end_turn(secs(-14), None), (secs(4), None));
+        assert_eq!(
+            overtime.end_turn(secs(-40), None),
+            (TimeDelta::zero(), None)
+        );
+    }
+
+    #[test]
+    fn test_canadian() {
+        let overtime = Overtime::Canadian {
+            moves: 2,
+            period: secs(60),
+        };
+        assert_eq!(overtime.standing(secs(1), None), Standing::Main);
+        assert_eq!(
+            overtime.standing(secs(-20), None),
+            Standing::Canadian {
+                remaining: secs(40),
+                moves: 2
+            }
+        );
+
+        // the first move only counts down the moves
+        assert_eq!(
+            overtime.end_turn(secs(-20), None),
+            (TimeDelta::zero(), Some(1))
+        );
+        assert_eq!(
+            overtime.standing(secs(-20), Some(1)),
+            Standing::Canadian {
+                remaining: secs(40),
+                moves: 1
+            }
+        );
+
+        // the last move of the period resets it
+        assert_eq!(overtime.end_turn(secs(-50), Some(1)), (secs(50), Some(0)));
+        assert_eq!(
+            overtime.standing(secs(0), Some(0)),
+            Standing::Canadian {
+                remaining: secs(60),
+                moves: 2
+            }
+        );
+        assert_eq!(overtime.standing(secs(-60), Some(1)), Standing::Lost);
+    }
+}
@@ -0,0 +1,185 @@
+use std::{fmt::Display, str::FromStr};
+
+use chrono::TimeDelta;
+use serde::{Deserialize, Deserializer, Serialize};
+
+use super::snapshot::{positive_millis, text};
+
+/// A named stretch of a [`Sequence`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Segment {
+    pub name: String,
+    #[serde(with = "positive_millis")]
+    pub duration: TimeDelta,
+}
+
+impl Segment {
+    pub fn new(name: &str, duration: TimeDelta) -> Self {
+        Self {
+            name: name.trim().to_string(),
+            duration,
+        }
+    }
+}
+
+/// When a [`Sequence`] moves on from a segment whose time is up.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Advance {
+    /// Straight away, as soon as the segment runs out.
+    #[default]
+    Auto,
+    /// Only when someone taps next, so a segment can run over.
+    Tap,
+}
+
+impl Display for Advance {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Advance::Auto => write!(f, "auto"),
+            Advance::Tap => write!(f, "tap"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAdvanceError(String);
+
+impl Display for ParseAdvanceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "unknown way to advance: {:?}", self.0)
+    }
+}
+
+impl FromStr for Advance {
+    type Err = ParseAdvanceError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(Advance::Auto),
+            "tap" => Ok(Advance::Tap),
+            _ => Err(ParseAdvanceError(s.to_string())),
+        }
+    }
+}
+
+/// Named segments timed one after another, such as the speeches of a debate
+/// or the rounds of an interval workout, played through `rounds` times.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sequence {
+    pub segments: Vec<Segment>,
+    #[serde(with = "text")]
+    pub advance: Advance,
+    #[serde(deserialize_with = "rounds")]
+    pub rounds: u32,
+}
+
+/// Rounds from a link are held to what the lobby offers.
+fn rounds<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+    u32::deserialize(deserializer).map(|rounds| rounds.clamp(1, Sequence::MAX_ROUNDS))
+}
+
+impl Default for Sequence {
+    fn default() -> Self {
+        Self {
+            segments: vec![
+                Segment::new("Opening", TimeDelta::minutes(2)),
+                Segment::new("Rebuttal", TimeDelta::minutes(1)),
+                Segment::new("Closing", TimeDelta::seconds(30)),
+            ],
+            advance: Advance::default(),
+            rounds: 1,
+        }
+    }
+}
+
+impl Sequence {
+    /// The most rounds the lobby offers.
+    pub const MAX_ROUNDS: u32 = 99;
+
+    /// How many segments are played in all, counting every round.
+    pub fn steps(&self) -> u32 {
+        (self.segments.len() as u32).saturating_mul(self.rounds.max(1))
+    }
+
+    /// The segment played at `step`, or `None` once the sequence is over.
+    pub fn segment(&self, step: u32) -> Option<&Segment> {
+        if step >= self.steps() {
+            return None;
+        }
+        self.segments.get(step as usize % self.segments.len())
+    }
+
+    /// Which round `step` belongs to, from one.
+    pub fn round(&self, step: u32) -> u32 {
+        step / (self.segments.len() as u32).max(1) + 1
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_steps() {
+        let sequence = Sequence {
+            rounds: 2,
+            ..Default::default()
+        };
+        assert_eq!(sequence.steps(), 6);
+        // rounds from a hand-made link cannot overflow
+        let endless = Sequence {
+            rounds: u32::MAX,
+            ..Default::default()
+        };
+        assert_eq!(endless.steps(), u32::MAX);
+        assert_eq!(endless.segment(u32::MAX), None);
+        let names = (0..7)
+            .map(|step| sequence.segment(step).map(|s| s.name.as_str()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                Some("Opening"),
+                Some("Rebuttal"),
+                Some("Closing"),
+                Some("Opening"),
+                Some("Rebuttal"),
+                Some("Closing"),
+                None,
+            ]
+        );
+        assert_eq!(sequence.round(2), 1);
+        assert_eq!(sequence.round(3), 2);
+
+        let empty = Sequence {
+            segments: Vec::new(),
+            ..Default::default()
+        };
+        assert_eq!(empty.segment(0), None);
+        assert_eq!(empty.round(0), 1);
+    }
+
+    #[test]
+    fn test_sequence_from_link() {
+        let decode = |json: &str| serde_json::from_str::<Sequence>(json);
+        let segments = r#"[{"name":"Talk","duration":60000}]"#;
+        let sequence =
+            decode(&format!(r#"{{"segments":{segments},"rounds":4000000000}}"#)).unwrap();
+        assert_eq!(sequence.rounds, Sequence::MAX_ROUNDS);
+        assert_eq!(sequence.segments[0].duration, TimeDelta::minutes(1));
+        assert_eq!(decode(r#"{"rounds":0}"#).unwrap().rounds, 1);
+        for duration in [0, -1000] {
+            let json = format!(r#"{{"segments":[{{"name":"Talk","duration":{duration}}}]}}"#);
+            assert!(decode(&json).is_err());
+        }
+    }
+
+    #[test]
+    fn test_advance_round_trip() {
+        for advance in [Advance::Auto, Advance::Tap] {
+            assert_eq!(advance.to_string().parse::<Advance>(), Ok(advance));
+        }
+        assert!("later".parse::<Advance>().is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{bank::Rules, duration::MAX, event::TimerState, history::History};
+
+/// Serializable state of a [`super::stopwatch::Stopwatch`], minus its clock
+/// and any undelivered events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StopwatchSnapshot {
+    pub state: TimerState,
+    /// Wall-clock time the stopwatch was last started, if it is running.
+    pub start: Option<DateTime<Utc>>,
+    #[serde(with = "millis")]
+    pub elapsed: TimeDelta,
+    /// `None` for a stopwatch that counts up.
+    #[serde(with = "millis_option")]
+    pub limit: Option<TimeDelta>,
+    #[serde(with = "millis", default)]
+    pub extension: TimeDelta,
+    #[serde(with = "millis", default)]
+    pub delay: TimeDelta,
+    /// Extensions taken so far this game, counted against the budget.
+    #[serde(default)]
+    pub extensions: u32,
+    #[serde(with = "millis", default)]
+    pub extended: TimeDelta,
+}
+
+/// Serializable state of a [`super::Bank`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BankSnapshot {
+    pub rules: Rules,
+    pub stopwatches: Vec<StopwatchSnapshot>,
+    #[serde(default)]
+    pub moves: Vec<Option<u32>>,
+    pub active: usize,
+    #[serde(with = "millis", default)]
+    pub turn_start: TimeDelta,
+    #[serde(with = "millis", default)]
+    pub turn_extension: TimeDelta,
+    #[serde(default)]
+    pub history: History,
+    #[serde(default)]
+    pub step: u32,
+}
+
+impl BankSnapshot {
+    /// Whether anything has happened on the clock worth resuming.
+    pub fn started(&self) -> bool {
+        !self.history.laps().is_empty()
+            || self
+                .stopwatches
+                .iter()
+                .any(|stopwatch| stopwatch.state != TimerState::Idle)
+    }
+}
+
+/// Reads `millis` as a duration no longer than [`MAX`] either way, so that
+/// a hand-made link cannot overflow the arithmetic on it.
+fn checked<E: serde::de::Error>(millis: i64) -> Result<TimeDelta, E> {
+    TimeDelta::try_milliseconds(millis)
+        .filter(|delta| delta.abs() <= MAX)
+        .ok_or_else(|| E::custom(format!("duration out of range: {millis}ms")))
+}
+
+/// Durations as a whole number of milliseconds.
+pub(super) mod millis {
+    use chrono::TimeDelta;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(delta: &TimeDelta, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(delta.num_milliseconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+        super::checked(i64::deserialize(deserializer)?)
+    }
+}
+
+/// Durations that must be longer than zero, such as the phases of a cycle,
+/// which would otherwise end as soon as they began.
+pub(super) mod positive_millis {
+    use chrono::TimeDelta;
+    use serde::{de::Error, Deserializer};
+
+    pub use super::millis::serialize;
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<TimeDelta, D::Error> {
+        let delta = super::millis::deserialize(deserializer)?;
+        if delta <= TimeDelta::zero() {
+            return Err(D::Error::custom("duration must be longer than zero"));
+        }
+        Ok(delta)
+    }
+}
+
+/// Optional limits as a whole number of milliseconds, or `null`. Like a
+/// [`super::Limit`], a limit must be longer than zero.
+pub(super) mod millis_option {
+    use chrono::TimeDelta;
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        delta: &Option<TimeDelta>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match delta {
+            Some(delta) => serializer.serialize_some(&delta.num_milliseconds()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<TimeDelta>, D::Error> {
+        let Some(millis) = Option::<i64>::deserialize(deserializer)? else {
+            return Ok(None);
+        };
+        match super::checked(millis)? {
+            delta if delta <= TimeDelta::zero() => {
+                Err(D::Error::custom("duration must be longer than zero"))
+            }
+            delta => Ok(Some(delta)),
+        }
+    }
+}
+
+/// Values in the same compact text form used by the route, e.g. `fischer:5`.
+pub(super) mod text {
+    use std::{fmt::Display, str::FromStr};
+
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T: Display, S: Serializer>(
+        value: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: FromStr,
+        T::Err: Display,
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
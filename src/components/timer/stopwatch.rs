@@ -0,0 +1,528 @@
+use std::{fmt::Display, ops::AddAssign};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::{
+    adjust::{Allowance, Budget, ExtendError},
+    clock::Clock,
+    event::{TimerEvent, TimerState},
+    format::Format,
+    snapshot::StopwatchSnapshot,
+};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Stopwatch<C: Clock> {
+    clock: C,
+    state: TimerState,
+    start: Option<DateTime<Utc>>,
+    elapsed: TimeDelta,
+    /// `None` for a stopwatch that just counts up.
+    limit: Option<TimeDelta>,
+    extension: TimeDelta,
+    delay: TimeDelta,
+    budget: Budget,
+    /// Extensions taken over the whole game, unlike `extension`.
+    extensions: u32,
+    extended: TimeDelta,
+    events: Vec<TimerEvent>,
+}
+
+impl<C: Clock> Stopwatch<C> {
+    pub fn new(clock: C, limit: TimeDelta) -> Self {
+        Self {
+            clock,
+            state: TimerState::Idle,
+            start: None,
+            elapsed: TimeDelta::zero(),
+            limit: Some(limit),
+            extension: TimeDelta::zero(),
+            delay: TimeDelta::zero(),
+            budget: Budget::default(),
+            extensions: 0,
+            extended: TimeDelta::zero(),
+            events: Vec::new(),
+        }
+    }
+
+    /// A stopwatch without a limit, which never runs out.
+    pub fn counting_up(clock: C) -> Self {
+        Self {
+            limit: None,
+            ..Self::new(clock, TimeDelta::zero())
+        }
+    }
+
+    /// Changes the limit, as when a sequence moves on to its next phase.
+    pub fn set_limit(&mut self, limit: TimeDelta) {
+        self.limit = Some(limit);
+    }
+
+    pub fn with_budget(self, budget: Budget) -> Self {
+        Self { budget, ..self }
+    }
+
+    pub fn restore(clock: C, snapshot: StopwatchSnapshot) -> Self {
+        Self {
+            clock,
+            state: snapshot.state,
+            start: snapshot.start,
+            elapsed: snapshot.elapsed,
+            limit: snapshot.limit,
+            extension: snapshot.extension,
+            delay: snapshot.delay,
+            budget: Budget::default(),
+            extensions: snapshot.extensions,
+            extended: snapshot.extended,
+            events: Vec::new(),
+        }
+    }
+
+    pub fn snapshot(&self) -> StopwatchSnapshot {
+        StopwatchSnapshot {
+            state: self.state,
+            start: self.start,
+            elapsed: self.elapsed,
+            limit: self.limit,
+            extension: self.extension,
+            delay: self.delay,
+            extensions: self.extensions,
+            extended: self.extended,
+        }
+    }
+
+    pub fn start(&mut self) {
+        if self.start.is_none() {
+            self.start = Some(self.clock.now());
+            let event = match self.state {
+                TimerState::Idle => TimerEvent::Started,
+                _ => TimerEvent::Resumed,
+            };
+            if self.state != TimerState::Expired {
+                self.state = TimerState::Running;
+            }
+            self.events.push(event);
+        }
+    }
+
+    pub fn stop(&mut self) {
+        if self.start.is_some() {
+            self.settle();
+            self.start = None;
+            if self.state == TimerState::Running {
+                self.state = TimerState::Paused;
+            }
+            self.events.push(TimerEvent::Paused);
+        }
+    }
+
+    pub fn toggle(&mut self) {
+        if self.start.is_some() {
+            self.stop();
+        } else {
+            self.start();
+        }
+    }
+
+    pub fn lap(&mut self) -> TimeDelta {
+        self.lap_at(self.elapsed())
+    }
+
+    /// Ends the lap once `at` has elapsed, keeping whatever ran past it in
+    /// the next lap, as when one phase runs on into the next.
+    pub fn lap_at(&mut self, at: TimeDelta) -> TimeDelta {
+        self.settle();
+        let elapsed = self.elapsed.min(at);
+        self.elapsed -= elapsed;
+        self.extension = TimeDelta::zero();
+        self.delay = TimeDelta::zero();
+        if self.start.is_some() {
+            self.start = Some(self.clock.now());
+            self.state = TimerState::Running;
+        } else {
+            self.state = TimerState::Idle;
+        }
+        self.events.push(TimerEvent::Lapped(elapsed));
+        elapsed
+    }
+
+    /// Adds time by hand, within the budget, returning how much was added.
+    /// Taking time away always succeeds.
+    pub fn extend_time(&mut self, delta: TimeDelta) -> Result<TimeDelta, ExtendError> {
+        if delta <= TimeDelta::zero() {
+            self.credit(delta);
+            return Ok(delta);
+        }
+        let granted = self.budget.grant(self.extensions, self.extended, delta)?;
+        self.extensions += 1;
+        self.extended += granted;
+        self.credit(granted);
+        Ok(granted)
+    }
+
+    pub fn allowance(&self) -> Allowance {
+        self.budget.left(self.extensions, self.extended)
+    }
+
+    /// Adds time the rules grant, such as an increment, outside the budget.
+    pub fn credit(&mut self, delta: TimeDelta) {
+        self.extension += delta;
+        self.events.push(TimerEvent::Extended(delta));
+        let rearmed = self
+            .remaining()
+            .is_some_and(|left| left > TimeDelta::zero());
+        if self.state == TimerState::Expired && rearmed {
+            self.state = match self.start {
+                Some(_) => TimerState::Running,
+                None => TimerState::Paused,
+            };
+        }
+    }
+
+    /// Checks for expiry, emitting [`TimerEvent::Expired`] on the transition.
+    pub fn tick(&mut self) {
+        let started = matches!(self.state, TimerState::Running | TimerState::Paused);
+        if started
+            && self
+                .remaining()
+                .is_some_and(|left| left <= TimeDelta::zero())
+        {
+            self.state = TimerState::Expired;
+            self.events.push(TimerEvent::Expired);
+        }
+    }
+
+    pub fn state(&self) -> TimerState {
+        self.state
+    }
+
+    /// Drains the events emitted since the last call.
+    pub fn take_events(&mut self) -> Vec<TimerEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    /// Folds the time run since `start` into `elapsed`, minus any delay.
+    fn settle(&mut self) {
+        if let Some(start) = self.start {
+            let now = self.clock.now();
+            let run = now - start;
+            self.elapsed += (run - self.delay).max(TimeDelta::zero());
+            self.delay = (self.delay - run).max(TimeDelta::zero());
+            self.start = Some(now);
+        }
+    }
+
+    /// Grants a grace period that passes before the stopwatch starts counting.
+    pub fn set_delay(&mut self, delay: TimeDelta) {
+        self.settle();
+        self.delay = delay;
+    }
+
+    pub fn delay_remaining(&self) -> TimeDelta {
+        match self.start {
+            Some(start) => (self.delay - (self.clock.now() - start)).max(TimeDelta::zero()),
+            None => self.delay,
+        }
+    }
+
+    pub fn total(&self) -> Option<TimeDelta> {
+        self.limit.map(|limit| limit + self.extension)
+    }
+
+    pub fn running(&self) -> bool {
+        self.start.is_some()
+    }
+
+    pub fn elapsed(&self) -> TimeDelta {
+        if let Some(start) = self.start {
+            self.elapsed + (self.clock.now() - start - self.delay).max(TimeDelta::zero())
+        } else {
+            self.elapsed
+        }
+    }
+
+    /// Time left before the limit, or `None` when counting up.
+    pub fn remaining(&self) -> Option<TimeDelta> {
+        Some(self.total()? - self.elapsed())
+    }
+
+    /// What the clock face shows: the time left, or else the time spent.
+    pub fn face(&self) -> TimeDelta {
+        self.remaining().unwrap_or_else(|| self.elapsed())
+    }
+
+    /// Share of the limit used, or `None` when counting up.
+    pub fn progress(&self) -> Option<f32> {
+        let (elapsed, total) = (self.elapsed(), self.total()?);
+        Some(if elapsed >= total {
+            1.0
+        } else if elapsed <= TimeDelta::zero() {
+            0.0
+        } else {
+            elapsed.as_seconds_f32() / total.as_seconds_f32()
+        })
+    }
+}
+
+impl<C: Clock + Clone> Clone for Stopwatch<C> {
+    fn clone(&self) -> Self {
+        Self {
+            clock: self.clock.clone(),
+            state: self.state,
+            start: self.start,
+            elapsed: self.elapsed,
+            limit: self.limit,
+            extension: self.extension,
+            delay: self.delay,
+            budget: self.budget,
+            extensions: self.extensions,
+            extended: self.extended,
+            events: self.events.clone(),
+        }
+    }
+}
+
+/// Formats a remaining duration as `MM:SS`.
+pub struct Countdown(pub TimeDelta);
+
+impl Display for Countdown {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Format::default().apply(self.0).fmt(f)
+    }
+}
+
+impl<C: Clock> Display for Stopwatch<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Countdown(self.face()).fmt(f)
+    }
+}
+
+impl<C: Clock> AddAssign<TimeDelta> for Stopwatch<C> {
+    fn add_assign(&mut self, delta: TimeDelta) {
+        self.credit(delta);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::clock::MockClock;
+    use super::*;
+
+    #[test]
+    fn test_stopwatch_progress() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut sw = Stopwatch::new(clock.clone(), TimeDelta::seconds(2));
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(2)));
+        assert_eq!(sw.progress(), Some(0.0));
+
+        // time advancing without starting the stopwatch should not change remaining or progress
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(2)));
+        assert_eq!(sw.progress(), Some(0.0));
+
+        // start the stopwatch and check that it counts down
+        sw.start();
+
+        // time advancing while running should decrease remaining and increase progress
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(1)));
+        assert_eq!(sw.progress(), Some(0.5));
+
+        // time advancing while running should decrease remaining and increase progress
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::zero()));
+        assert_eq!(sw.progress(), Some(1.0));
+
+        // time advancing after reaching the end should change remaining but not progress (max progress is 1.0)
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(-1)));
+        assert_eq!(sw.progress(), Some(1.0));
+
+        // stop the stopwatch and check that it stops counting down
+        sw.stop();
+
+        // time advancing while stopped
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(-1)));
+        assert_eq!(sw.progress(), Some(1.0));
+    }
+
+    #[test]
+    fn test_stopwatch_display() {
+        //todo: Implement Display trait test for Stopwatch
+
+        let clock = MockClock::new(0, 0);
+
+        let sw0 = Stopwatch::new(clock.clone(), TimeDelta::seconds(0));
+        assert_eq!(sw0.to_string(), "00:00");
+
+        let sw90 = Stopwatch::new(clock.clone(), TimeDelta::seconds(90));
+        assert_eq!(sw90.to_string(), "01:30");
+
+        let sw150 = Stopwatch::new(clock.clone(), TimeDelta::seconds(150));
+        assert_eq!(sw150.to_string(), "02:30");
+
+        let sw5999 = Stopwatch::new(clock.clone(), TimeDelta::seconds(5999));
+        assert_eq!(sw5999.to_string(), "99:59");
+
+        let sw7425 = Stopwatch::new(clock.clone(), TimeDelta::seconds(7425));
+        assert_eq!(sw7425.to_string(), "123:45");
+    }
+
+    #[test]
+    fn test_stopwatch_delay() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut sw = Stopwatch::new(clock.clone(), TimeDelta::seconds(10));
+        sw.set_delay(TimeDelta::seconds(3));
+        sw.start();
+
+        // the clock does not count down during the delay
+        clock += TimeDelta::seconds(2);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(10)));
+        assert_eq!(sw.delay_remaining(), TimeDelta::seconds(1));
+
+        // pausing keeps whatever is left of the delay
+        sw.stop();
+        clock += TimeDelta::seconds(5);
+        assert_eq!(sw.delay_remaining(), TimeDelta::seconds(1));
+        sw.start();
+
+        clock += TimeDelta::seconds(3);
+        assert_eq!(sw.delay_remaining(), TimeDelta::zero());
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(8)));
+
+        // a lap starts without any delay unless one is granted again
+        sw.lap();
+        clock += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(9)));
+    }
+
+    #[test]
+    fn test_stopwatch_state_machine() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut sw = Stopwatch::new(clock.clone(), TimeDelta::seconds(2));
+        assert_eq!(sw.state(), TimerState::Idle);
+
+        sw.start();
+        assert_eq!(sw.state(), TimerState::Running);
+        sw.stop();
+        assert_eq!(sw.state(), TimerState::Paused);
+        sw.toggle();
+        assert_eq!(
+            sw.take_events(),
+            vec![TimerEvent::Started, TimerEvent::Paused, TimerEvent::Resumed]
+        );
+
+        // expiry is edge-triggered and fires exactly once
+        clock += TimeDelta::seconds(1);
+        sw.tick();
+        assert!(sw.take_events().is_empty());
+        clock += TimeDelta::seconds(1);
+        sw.tick();
+        clock += TimeDelta::seconds(1);
+        sw.tick();
+        assert_eq!(sw.state(), TimerState::Expired);
+        assert_eq!(sw.take_events(), vec![TimerEvent::Expired]);
+
+        // pausing an expired stopwatch keeps it expired
+        sw.toggle();
+        sw.toggle();
+        assert_eq!(sw.state(), TimerState::Expired);
+        assert_eq!(
+            sw.take_events(),
+            vec![TimerEvent::Paused, TimerEvent::Resumed]
+        );
+
+        // adding time re-arms the expiry
+        sw += TimeDelta::seconds(2);
+        assert_eq!(sw.state(), TimerState::Running);
+        clock += TimeDelta::seconds(1);
+        sw.tick();
+        assert_eq!(
+            sw.take_events(),
+            vec![
+                TimerEvent::Extended(TimeDelta::seconds(2)),
+                TimerEvent::Expired
+            ]
+        );
+
+        sw.stop();
+        assert_eq!(sw.lap(), TimeDelta::seconds(4));
+        assert_eq!(sw.state(), TimerState::Idle);
+        assert_eq!(
+            sw.take_events(),
+            vec![
+                TimerEvent::Paused,
+                TimerEvent::Lapped(TimeDelta::seconds(4))
+            ]
+        );
+    }
+
+    #[test]
+    fn test_add_time() {
+        let clock = MockClock::new(0, 0);
+
+        let mut sw = Stopwatch::new(clock.clone(), TimeDelta::seconds(2));
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(2)));
+
+        sw += TimeDelta::seconds(1);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(3)));
+
+        sw += TimeDelta::seconds(-2);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(1)));
+    }
+
+    #[test]
+    fn test_extension_budget() {
+        let clock = MockClock::new(0, 0);
+        let budget = Budget {
+            count: Some(2),
+            total: Some(TimeDelta::seconds(45)),
+        };
+        let mut sw = Stopwatch::new(clock.clone(), TimeDelta::seconds(60)).with_budget(budget);
+
+        assert_eq!(
+            sw.extend_time(TimeDelta::seconds(30)),
+            Ok(TimeDelta::seconds(30))
+        );
+        // only what is left of the total is granted
+        assert_eq!(
+            sw.extend_time(TimeDelta::seconds(30)),
+            Ok(TimeDelta::seconds(15))
+        );
+        assert_eq!(
+            sw.extend_time(TimeDelta::seconds(30)),
+            Err(ExtendError::Count(2))
+        );
+        assert_eq!(
+            sw.extend_time(TimeDelta::seconds(-10)),
+            Ok(TimeDelta::seconds(-10))
+        );
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(95)));
+
+        // the budget lasts the whole game, not just the turn
+        sw.lap();
+        assert_eq!(sw.allowance().count, Some(0));
+        sw += TimeDelta::seconds(5);
+        assert_eq!(sw.remaining(), Some(TimeDelta::seconds(65)));
+    }
+
+    #[test]
+    fn test_counting_up() {
+        let mut clock = MockClock::new(0, 0);
+
+        let mut sw = Stopwatch::counting_up(clock.clone());
+        sw.start();
+        clock += TimeDelta::seconds(3600);
+        sw.tick();
+
+        // never runs out, and shows the time spent instead
+        assert_eq!(sw.state(), TimerState::Running);
+        assert_eq!(sw.remaining(), None);
+        assert_eq!(sw.progress(), None);
+        assert_eq!(sw.to_string(), "60:00");
+        assert_eq!(sw.take_events(), vec![TimerEvent::Started]);
+    }
+}
@@ -0,0 +1,74 @@
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use super::snapshot::millis;
+
+/// How urgent the time left on a clock is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    Normal,
+    Warning,
+    Critical,
+    /// Time is up.
+    Overtime,
+}
+
+/// Time left at which a clock starts to warn. Zero turns a threshold off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Thresholds {
+    #[serde(with = "millis")]
+    pub warning: TimeDelta,
+    #[serde(with = "millis")]
+    pub critical: TimeDelta,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            warning: TimeDelta::seconds(30),
+            critical: TimeDelta::seconds(10),
+        }
+    }
+}
+
+impl Thresholds {
+    pub fn level(&self, remaining: TimeDelta) -> Level {
+        if remaining <= TimeDelta::zero() {
+            Level::Overtime
+        } else if remaining <= self.critical {
+            Level::Critical
+        } else if remaining <= self.warning {
+            Level::Warning
+        } else {
+            Level::Normal
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels() {
+        let thresholds = Thresholds::default();
+        assert_eq!(thresholds.level(TimeDelta::seconds(31)), Level::Normal);
+        assert_eq!(thresholds.level(TimeDelta::seconds(30)), Level::Warning);
+        assert_eq!(thresholds.level(TimeDelta::seconds(10)), Level::Critical);
+        assert_eq!(
+            thresholds.level(TimeDelta::milliseconds(1)),
+            Level::Critical
+        );
+        assert_eq!(thresholds.level(TimeDelta::zero()), Level::Overtime);
+        assert_eq!(thresholds.level(TimeDelta::seconds(-5)), Level::Overtime);
+
+        // a threshold of zero never triggers
+        let off = Thresholds {
+            warning: TimeDelta::zero(),
+            critical: TimeDelta::zero(),
+        };
+        assert_eq!(off.level(TimeDelta::seconds(1)), Level::Normal);
+        assert_eq!(off.level(TimeDelta::zero()), Level::Overtime);
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use super::{duration::Limit, snapshot::BankSnapshot};
+
+/// Commands kept for undoing, oldest first dropped.
+const DEPTH: usize = 100;
+
+/// A timer action that can be undone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Start,
+    Pause,
+    /// Ended the turn and handed the clock to the next player.
+    Lap,
+    /// Handed the clock to this player without ending the turn.
+    Select(usize),
+    Extend(TimeDelta),
+}
+
+impl Display for Command {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Command::Start => write!(f, "Started"),
+            Command::Pause => write!(f, "Paused"),
+            Command::Lap => write!(f, "Next turn"),
+            Command::Select(index) => write!(f, "Switched to player {}", index + 1),
+            Command::Extend(delta) if *delta < TimeDelta::zero() => {
+                write!(f, "Took {}", Limit(-*delta))
+            }
+            Command::Extend(delta) => write!(f, "Added {}", Limit(*delta)),
+        }
+    }
+}
+
+/// What last happened to the history, for the toast offering to reverse it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    Done(Command),
+    Undone(Command),
+    Redone(Command),
+}
+
+/// Snapshots of the bank taken around each command. Restoring one puts every
+/// clock back exactly as it was, so a clock that was running keeps counting
+/// the time since as if the command had never happened.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct Undo {
+    done: Vec<(Command, BankSnapshot)>,
+    undone: Vec<(Command, BankSnapshot)>,
+    last: Option<(Step, DateTime<Utc>)>,
+}
+
+impl Undo {
+    /// Records `command`, which was run on the bank described by `before`.
+    pub fn push(&mut self, command: Command, before: BankSnapshot, now: DateTime<Utc>) {
+        if self.done.len() == DEPTH {
+            self.done.remove(0);
+        }
+        self.done.push((command, before));
+        self.undone.clear();
+        self.last = Some((Step::Done(command), now));
+    }
+
+    /// The snapshot to go back to, given the bank as it is `current`ly.
+    pub fn undo(
+        &mut self,
+        current: BankSnapshot,
+        now: DateTime<Utc>,
+    ) -> Option<(Command, BankSnapshot)> {
+        let (command, before) = self.done.pop()?;
+        self.undone.push((command, current));
+        self.last = Some((Step::Undone(command), now));
+        Some((command, before))
+    }
+
+    pub fn redo(
+        &mut self,
+        current: BankSnapshot,
+        now: DateTime<Utc>,
+    ) -> Option<(Command, BankSnapshot)> {
+        let (command, after) = self.undone.pop()?;
+        self.done.push((command, current));
+        self.last = Some((Step::Redone(command), now));
+        Some((command, after))
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.done.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.undone.is_empty()
+    }
+
+    /// The latest step, if it happened at or after `since`.
+    pub fn last_since(&self, since: DateTime<Utc>) -> Option<Step> {
+        self.last
+            .filter(|(_, at)| *at >= since)
+            .map(|(step, _)| step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_command_display() {
+        assert_eq!(Command::Lap.to_string(), "Next turn");
+        assert_eq!(
+            Command::Extend(TimeDelta::seconds(30)).to_string(),
+            "Added 30s"
+        );
+        assert_eq!(
+            Command::Extend(TimeDelta::seconds(-90)).to_string(),
+            "Took 1m30s"
+        );
+    }
+}
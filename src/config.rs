@@ -0,0 +1,230 @@
+use std::{fmt::Display, str::FromStr};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::components::{Adjustments, Format, Roster, Rules, TableItem, Thresholds};
+use crate::sound::Sound;
+use crate::speech::Speech;
+
+/// Bumped whenever a change to [`Config`] would misread older links.
+const VERSION: u32 = 2;
+
+/// Everything chosen in the lobby, carried in the [`crate::Route::Play`] query
+/// so that a link reproduces the exact setup on another device.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[serde(default)]
+pub struct Config {
+    pub rules: Rules,
+    pub format: Format,
+    pub thresholds: Thresholds,
+    pub adjustments: Adjustments,
+    pub sound: Sound,
+    pub speech: Speech,
+    pub players: Roster,
+    /// The checklist to start the table with, or empty to keep the table as
+    /// it is. Play moves it to the table, so saved sessions leave it empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub table: Vec<TableItem>,
+}
+
+/// `<version>.<base64url JSON>`, which needs no escaping in a query string.
+impl Display for Config {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| std::fmt::Error)?;
+        write!(f, "{VERSION}.{}", URL_SAFE_NO_PAD.encode(json))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseConfigError(String);
+
+impl Display for ParseConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid config: {}", self.0)
+    }
+}
+
+impl FromStr for Config {
+    type Err = ParseConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = |reason: &dyn Display| ParseConfigError(reason.to_string());
+        let (version, data) = s.split_once('.').ok_or_else(|| err(&"missing version"))?;
+        let version = match version.parse::<u32>() {
+            Ok(version @ 1..=VERSION) => version,
+            _ => return Err(err(&format!("unsupported version {version:?}"))),
+        };
+        let json = URL_SAFE_NO_PAD.decode(data).map_err(|e| err(&e))?;
+        let mut doc: Value = serde_json::from_slice(&json).map_err(|e| err(&e))?;
+        if version == 1 {
+            if let Some(adjustments) = extension_adjustments(doc.get_mut("rules")) {
+                if let Some(doc) = doc.as_object_mut() {
+                    doc.entry("adjustments").or_insert(adjustments);
+                }
+            }
+        }
+        serde_json::from_value(doc).map_err(|e| err(&e))
+    }
+}
+
+/// Version 1 had a single button adding `rules.extension` milliseconds. This
+/// takes it out of `rules` and returns it as the equivalent [`Adjustments`].
+pub fn extension_adjustments(rules: Option<&mut Value>) -> Option<Value> {
+    let millis = rules?.as_object_mut()?.remove("extension")?.as_i64()?;
+    let buttons = match millis {
+        ..=0 => vec![],
+        millis => vec![TimeDelta::milliseconds(millis)],
+    };
+    Some(Adjustments(buttons).to_string().into())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::{Advance, Budget, Cycle, Limit, Mode, Overtime, Sequence, TimeControl};
+
+    use super::*;
+
+    #[test]
+    fn test_config_round_trip() {
+        let mut players = Roster::default();
+        players.add("Ann");
+        players.add("Bob & Co");
+        let config = Config {
+            rules: Rules {
+                mode: Mode::Chess,
+                limit: Some(TimeDelta::minutes(5)),
+                control: TimeControl::Fischer(TimeDelta::seconds(3)),
+                overtime: Overtime::ByoYomi {
+                    periods: 3,
+                    period: TimeDelta::seconds(30),
+                },
+                budget: Budget {
+                    count: Some(3),
+                    total: Some(TimeDelta::minutes(2)),
+                },
+                cycle: Cycle {
+                    every: 3,
+                    ..Default::default()
+                },
+                sequence: Sequence {
+                    advance: Advance::Tap,
+                    rounds: 2,
+                    ..Default::default()
+                },
+            },
+            format: "hms,plus".parse().unwrap(),
+            thresholds: Thresholds {
+                warning: TimeDelta::seconds(60),
+                critical: TimeDelta::zero(),
+            },
+            adjustments: "-10s,+15s,+1m".parse().unwrap(),
+            sound: Sound {
+                volume: 80,
+                muted: false,
+                ticks: 5,
+            },
+            speech: Speech {
+                enabled: true,
+                warning: "{player}: {left}".to_string(),
+                ..Default::default()
+            },
+            players,
+            table: vec![TableItem {
+                id: 0,
+                description: "Shuffle".to_string(),
+                completed: false,
+                timebox: Some(Limit(TimeDelta::minutes(2))),
+                step: None,
+            }],
+        };
+
+        let encoded = config.to_string();
+        assert!(encoded.starts_with("2."));
+        assert!(encoded
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "._-".contains(c)));
+        assert_eq!(encoded.parse(), Ok(config));
+    }
+
+    #[test]
+    fn test_config_omits_empty_table() {
+        let json = serde_json::to_string(&Config::default()).unwrap();
+        assert!(!json.contains("table"));
+    }
+
+    #[test]
+    fn test_config_defaults_missing_fields() {
+        let json = r#"{"rules":{"mode":"chess","budget":{"count":3}},"adjustments":"+1m"}"#;
+        let encoded = format!("2.{}", URL_SAFE_NO_PAD.encode(json));
+        assert_eq!(
+            encoded.parse(),
+            Ok(Config {
+                rules: Rules {
+                    mode: Mode::Chess,
+                    budget: Budget {
+                        count: Some(3),
+                        total: None,
+                    },
+                    ..Default::default()
+                },
+                adjustments: Adjustments(vec![TimeDelta::minutes(1)]),
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn test_config_v1_extension() {
+        let decode = |json: &str| format!("1.{}", URL_SAFE_NO_PAD.encode(json)).parse::<Config>();
+        let config = decode(r#"{"rules":{"mode":"chess","extension":15000}}"#).unwrap();
+        assert_eq!(config.rules.mode, Mode::Chess);
+        assert_eq!(
+            config.adjustments,
+            Adjustments(vec![TimeDelta::seconds(15)])
+        );
+        let config = decode(r#"{"rules":{"extension":0}}"#).unwrap();
+        assert_eq!(config.adjustments, Adjustments(vec![]));
+        // links made after the buttons came in already have them
+        let config = decode(r#"{"adjustments":"+1m"}"#).unwrap();
+        assert_eq!(config.adjustments, Adjustments(vec![TimeDelta::minutes(1)]));
+        // the extension is only understood in the version that had it
+        let encoded = format!(
+            "2.{}",
+            URL_SAFE_NO_PAD.encode(r#"{"rules":{"extension":15000}}"#)
+        );
+        assert_eq!(
+            encoded.parse::<Config>().unwrap().adjustments,
+            Adjustments::default()
+        );
+    }
+
+    #[test]
+    fn test_config_rejects_out_of_range_durations() {
+        let decode = |json: &str| format!("2.{}", URL_SAFE_NO_PAD.encode(json)).parse::<Config>();
+        assert!(decode(r#"{"rules":{"limit":360000000}}"#).is_ok());
+        for json in [
+            r#"{"rules":{"limit":-9223372036854775808}}"#,
+            r#"{"rules":{"limit":9223372036854775807}}"#,
+            r#"{"rules":{"limit":360000001}}"#,
+            r#"{"rules":{"limit":0}}"#,
+            r#"{"rules":{"budget":{"total":-1000}}}"#,
+            r#"{"rules":{"control":"fischer:4000000000"}}"#,
+            r#"{"thresholds":{"warning":9223372036854775807,"critical":0}}"#,
+            r#"{"adjustments":"+9999999h"}"#,
+        ] {
+            assert!(decode(json).is_err(), "{json}");
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_bad_input() {
+        assert!("".parse::<Config>().is_err());
+        assert!("e30".parse::<Config>().is_err());
+        assert!("3.e30".parse::<Config>().is_err());
+        assert!("1.!!".parse::<Config>().is_err());
+        assert!("1.e30".parse::<Config>().is_ok());
+    }
+}
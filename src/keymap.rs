@@ -0,0 +1,168 @@
+use std::collections::BTreeMap;
+
+use dioxus::prelude::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+/// Web storage key the remapped shortcuts are saved under.
+const KEY: &str = "moment.keymap";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Toggle,
+    Next,
+    Extend,
+    Shorten,
+    Undo,
+    Redo,
+    Fullscreen,
+    Help,
+}
+
+impl Action {
+    pub const ALL: [Action; 8] = [
+        Action::Toggle,
+        Action::Next,
+        Action::Extend,
+        Action::Shorten,
+        Action::Undo,
+        Action::Redo,
+        Action::Fullscreen,
+        Action::Help,
+    ];
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Action::Toggle => "Start or pause the clock",
+            Action::Next => "Next turn",
+            Action::Extend => "Add time (the first + button)",
+            Action::Shorten => "Take time away (the first - button)",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Fullscreen => "Toggle fullscreen",
+            Action::Help => "Show these shortcuts",
+        }
+    }
+}
+
+/// The keys bound to each action, named as in [`key_name`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Keymap(BTreeMap<Action, Vec<String>>);
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|key| key.to_string()).collect();
+        Self(BTreeMap::from([
+            (Action::Toggle, keys(&["Space"])),
+            (Action::Next, keys(&["n", "Enter"])),
+            (Action::Extend, keys(&["+"])),
+            (Action::Shorten, keys(&["-"])),
+            (Action::Undo, keys(&["u"])),
+            (Action::Redo, keys(&["r"])),
+            (Action::Fullscreen, keys(&["f"])),
+            (Action::Help, keys(&["?"])),
+        ]))
+    }
+}
+
+impl Keymap {
+    pub fn action(&self, key: &str) -> Option<Action> {
+        self.0
+            .iter()
+            .find(|(_, keys)| keys.iter().any(|k| k == key))
+            .map(|(action, _)| *action)
+    }
+
+    pub fn keys(&self, action: Action) -> &[String] {
+        self.0.get(&action).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every bound key, so the browser's own use of them can be suppressed.
+    pub fn bound(&self) -> Vec<String> {
+        self.0.values().flatten().cloned().collect()
+    }
+
+    /// Makes `key` the only key for `action`, taking it from any other action.
+    pub fn bind(&mut self, action: Action, key: &str) {
+        for keys in self.0.values_mut() {
+            keys.retain(|k| k != key);
+        }
+        self.0.insert(action, vec![key.to_string()]);
+    }
+
+    /// The shortcuts saved by the last visit, with defaults for any new actions.
+    pub fn load() -> Self {
+        let mut keymap = Self::default();
+        if let Some(json) = storage::get(KEY) {
+            match serde_json::from_str::<Self>(&json) {
+                Ok(saved) => keymap.0.extend(saved.0),
+                Err(err) => warn!("discarding saved shortcuts: {err}"),
+            }
+        }
+        keymap
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            storage::set(KEY, &json);
+        }
+    }
+}
+
+/// Names a `KeyboardEvent.key` the way the keymap stores it: letters in lower
+/// case, so that shift or caps lock don't matter, and the space bar as `Space`.
+pub fn key_name(key: &str) -> String {
+    match key {
+        " " => "Space".to_string(),
+        key if key.chars().count() == 1 => key.to_lowercase(),
+        key => key.to_string(),
+    }
+}
+
+/// How a key is shown in the help overlay.
+pub fn key_label(key: &str) -> String {
+    match key {
+        key if key.chars().count() == 1 => key.to_uppercase(),
+        key => key.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keymap() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action(&key_name(" ")), Some(Action::Toggle));
+        assert_eq!(keymap.action(&key_name("N")), Some(Action::Next));
+        assert_eq!(keymap.action(&key_name("Enter")), Some(Action::Next));
+        assert_eq!(keymap.action(&key_name("+")), Some(Action::Extend));
+        assert_eq!(keymap.action(&key_name("x")), None);
+        // every action can be reached from the keyboard
+        for action in Action::ALL {
+            assert!(!keymap.keys(action).is_empty(), "{action:?}");
+        }
+    }
+
+    #[test]
+    fn test_bind_moves_key() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Toggle, "n");
+        assert_eq!(keymap.action("n"), Some(Action::Toggle));
+        assert_eq!(keymap.action("Space"), None);
+        assert_eq!(keymap.keys(Action::Next), ["Enter"]);
+    }
+
+    #[test]
+    fn test_keymap_json() {
+        let mut keymap = Keymap::default();
+        keymap.bind(Action::Help, "h");
+        let json = serde_json::to_string(&keymap).unwrap();
+        assert!(json.contains(r#""help":["h"]"#));
+        assert_eq!(serde_json::from_str::<Keymap>(&json).unwrap(), keymap);
+    }
+}
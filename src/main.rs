@@ -1,4 +1,7 @@
+use components::{Limit, Roster, TableItem};
+use config::Config;
 use dioxus::prelude::*;
+use session::Session;
 use views::Lobby;
 use views::Play;
 
@@ -8,14 +11,40 @@ mod components;
 /// Define a views module that contains the UI for all Layouts and Routes for our app.
 mod views;
 
+/// The lobby's choices, shareable as part of a link.
+mod config;
+
+/// Tones played on timer events.
+mod sound;
+
+/// Announcements read aloud on timer events.
+mod speech;
+
+/// Keyboard shortcuts, remappable and kept across visits.
+mod keymap;
+
+/// Saved sessions, so that a reload can pick up where the timer left off.
+mod session;
+
+/// Settings kept in the browser between visits.
+mod storage;
+
+/// Checklists saved for starting the table from.
+mod templates;
+
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
+// routes are built once per navigation, so the size of `Play` does not matter
+#[allow(clippy::large_enum_variant)]
 enum Route {
     #[layout(NavBar)]
         #[route("/")]
         Lobby {},
-        #[route("/play?:time_limit")]
-        Play { time_limit: Option<u32> },
+        #[route("/play?:time_limit&:config")]
+        Play {
+            time_limit: Option<Limit>,
+            config: Config,
+        },
 }
 
 #[component]
@@ -53,6 +82,12 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    use_context_provider(|| Signal::new(Roster::default()));
+    // the table outlives any one game, so it is kept across pages and reloads
+    use_context_provider(|| {
+        Signal::new(Session::load().map_or_else(Vec::<TableItem>::new, |session| session.table))
+    });
+
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: MAIN_CSS }
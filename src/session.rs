@@ -0,0 +1,279 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use dioxus::prelude::warn;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::components::{restore_bank, BankSnapshot, TableItem, TimerBank};
+use crate::config::{extension_adjustments, Config};
+use crate::storage;
+
+/// Web storage key the current session is saved under.
+const KEY: &str = "moment.session";
+
+/// Upgrades a document from the version at its index + 1 to the next one.
+type Migration = fn(&mut Map<String, Value>);
+
+/// Every schema change appends a migration here, so old saves keep loading.
+const MIGRATIONS: &[Migration] = &[config_object, extension_buttons];
+
+/// Version 2 keeps the lobby's choices together, as in the link that started
+/// the game.
+fn config_object(doc: &mut Map<String, Value>) {
+    let mut config = Map::new();
+    for key in ["format", "thresholds", "players"] {
+        if let Some(value) = doc.remove(key) {
+            config.insert(key.to_string(), value);
+        }
+    }
+    doc.insert("config".to_string(), config.into());
+}
+
+/// Version 3 replaced the one button adding the rules' extension with the
+/// lobby's adjustments.
+fn extension_buttons(doc: &mut Map<String, Value>) {
+    let rules = doc
+        .get_mut("timer")
+        .and_then(|timer| timer.get_mut("rules"));
+    if let Some(adjustments) = extension_adjustments(rules) {
+        if let Some(config) = doc.get_mut("config").and_then(Value::as_object_mut) {
+            config.entry("adjustments").or_insert(adjustments);
+        }
+    }
+}
+
+pub const VERSION: u64 = MIGRATIONS.len() as u64 + 1;
+
+/// Everything needed to resume a game: the timer, who is playing and the table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub version: u64,
+    pub saved: DateTime<Utc>,
+    pub timer: BankSnapshot,
+    #[serde(default)]
+    pub config: Config,
+    #[serde(default)]
+    pub table: Vec<TableItem>,
+    /// Whether the timer is running the table as a meeting agenda.
+    #[serde(default)]
+    pub meeting: bool,
+}
+
+#[derive(Debug)]
+pub enum SessionError {
+    Json(serde_json::Error),
+    /// The document has no version, or one newer than this build understands.
+    Version(Option<u64>),
+}
+
+impl Display for SessionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionError::Json(err) => write!(f, "invalid session: {err}"),
+            SessionError::Version(Some(version)) => {
+                write!(f, "unsupported session version: {version}")
+            }
+            SessionError::Version(None) => write!(f, "session has no version"),
+        }
+    }
+}
+
+impl From<serde_json::Error> for SessionError {
+    fn from(err: serde_json::Error) -> Self {
+        SessionError::Json(err)
+    }
+}
+
+impl Session {
+    pub fn new(timer: BankSnapshot, config: Config, table: Vec<TableItem>, meeting: bool) -> Self {
+        Self {
+            version: VERSION,
+            saved: Utc::now(),
+            timer,
+            config,
+            table,
+            meeting,
+        }
+    }
+
+    /// Rebuilds the saved timer, if the snapshot describes a valid one.
+    pub fn bank(&self) -> Option<TimerBank> {
+        restore_bank(self.timer.clone())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("sessions always serialize")
+    }
+
+    /// Parses a document saved by this or any earlier version.
+    pub fn from_json(json: &str) -> Result<Self, SessionError> {
+        let mut doc: Value = serde_json::from_str(json)?;
+        migrate(&mut doc, MIGRATIONS)?;
+        Ok(serde_json::from_value(doc)?)
+    }
+
+    /// The session saved by the last visit, unless it is unreadable.
+    pub fn load() -> Option<Self> {
+        let json = storage::get(KEY)?;
+        Self::from_json(&json)
+            .inspect_err(|err| warn!("discarding saved session: {err}"))
+            .ok()
+    }
+
+    pub fn save(&self) {
+        storage::set(KEY, &self.to_json());
+    }
+}
+
+fn migrate(doc: &mut Value, migrations: &[Migration]) -> Result<(), SessionError> {
+    let latest = migrations.len() as u64 + 1;
+    let version = doc.get("version").and_then(Value::as_u64);
+    let Some(doc) = doc.as_object_mut() else {
+        return Err(SessionError::Version(None));
+    };
+    let mut version = match version {
+        Some(version @ 1..) if version <= latest => version,
+        _ => return Err(SessionError::Version(version)),
+    };
+    for migration in &migrations[version as usize - 1..] {
+        migration(doc);
+        version += 1;
+        doc.insert("version".to_string(), version.into());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A version 1 save, kept verbatim to make sure it always loads.
+    const V1: &str = r#"{
+        "version": 1,
+        "saved": "2025-01-01T12:00:00Z",
+        "timer": {
+            "rules": { "mode": "chess", "limit": 60000, "control": "fischer:5", "overtime": "byoyomi:3:30" },
+            "stopwatches": [
+                { "state": "paused", "start": null, "elapsed": 12000, "limit": 60000, "extension": 5000, "delay": 0 },
+                { "state": "running", "start": "2025-01-01T11:59:50Z", "elapsed": 3000, "limit": 60000 }
+            ],
+            "moves": [null, null],
+            "active": 1,
+            "turn_start": 3000,
+            "history": [
+                { "player": 0, "duration": 12000, "overrun": 0, "extension": 0, "finished": "2025-01-01T11:59:50Z" }
+            ]
+        },
+        "players": [{ "id": 0, "name": "Ann" }, { "id": 1, "name": "Bob" }],
+        "table": [{ "id": 0, "description": "Opening", "completed": true }]
+    }"#;
+
+    /// A version 2 save, with the lobby's choices kept together.
+    const V2: &str = r#"{
+        "version": 2,
+        "saved": "2025-06-01T12:00:00Z",
+        "timer": {
+            "rules": { "mode": "turn", "limit": 90000, "control": "none", "overtime": "none" },
+            "stopwatches": [
+                { "state": "idle", "start": null, "elapsed": 0, "limit": 90000 }
+            ],
+            "moves": [null],
+            "active": 0,
+            "turn_start": 0,
+            "history": []
+        },
+        "config": {
+            "format": "hms,tenths:10,plus,ceil",
+            "players": [{ "id": 0, "name": "Ann" }]
+        },
+        "table": []
+    }"#;
+
+    #[test]
+    fn test_session_round_trip() {
+        let session = Session::from_json(V1).unwrap();
+        assert_eq!(session.version, VERSION);
+        assert_eq!(session.timer.active, 1);
+        assert_eq!(session.timer.stopwatches.len(), 2);
+        assert_eq!(session.timer.history.laps().len(), 1);
+        assert_eq!(session.config.players.name(1), "Bob");
+        assert!(session.table[0].completed);
+        assert!(!session.meeting);
+        assert!(session.timer.started());
+        assert!(session.bank().is_some());
+
+        assert_eq!(Session::from_json(&session.to_json()).unwrap(), session);
+    }
+
+    #[test]
+    fn test_session_v2_config() {
+        let session = Session::from_json(V2).unwrap();
+        assert_eq!(session.version, VERSION);
+        assert_eq!(session.config.format.to_string(), "hms,tenths:10,plus,ceil");
+        assert_eq!(session.config.players.name(0), "Ann");
+        assert!(!session.timer.started());
+        assert!(session.bank().is_some());
+    }
+
+    #[test]
+    fn test_session_v2_extension() {
+        let json = V2.replace(
+            r#""overtime": "none" }"#,
+            r#""overtime": "none", "extension": 15000 }"#,
+        );
+        let session = Session::from_json(&json).unwrap();
+        assert_eq!(session.config.adjustments.to_string(), "+15s");
+        assert_eq!(
+            Session::from_json(V2).unwrap().config.adjustments,
+            Default::default()
+        );
+    }
+
+    #[test]
+    fn test_session_rejects_unknown_versions() {
+        for json in [
+            r#"{ "saved": "2025-01-01T12:00:00Z" }"#,
+            r#"{ "version": 0 }"#,
+            r#"{ "version": 999 }"#,
+            "[]",
+        ] {
+            assert!(matches!(
+                Session::from_json(json),
+                Err(SessionError::Version(_))
+            ));
+        }
+        assert!(matches!(
+            Session::from_json("not json"),
+            Err(SessionError::Json(_))
+        ));
+    }
+
+    #[test]
+    fn test_migrations_apply_in_order() {
+        let rename: Migration = |doc| {
+            let table = doc.remove("items").unwrap_or_default();
+            doc.insert("table".to_string(), table);
+        };
+        let count: Migration = |doc| {
+            let count = doc["table"].as_array().map_or(0, Vec::len);
+            doc.insert("count".to_string(), count.into());
+        };
+        let migrations = [rename, count];
+
+        let mut doc: Value = serde_json::from_str(r#"{ "version": 1, "items": [1, 2] }"#).unwrap();
+        migrate(&mut doc, &migrations).unwrap();
+        assert_eq!(
+            doc,
+            serde_json::json!({ "version": 3, "table": [1, 2], "count": 2 })
+        );
+
+        // documents already past a migration skip it
+        let mut doc: Value = serde_json::from_str(r#"{ "version": 2, "table": [] }"#).unwrap();
+        migrate(&mut doc, &migrations).unwrap();
+        assert_eq!(
+            doc,
+            serde_json::json!({ "version": 3, "table": [], "count": 0 })
+        );
+    }
+}
@@ -0,0 +1,220 @@
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Level, PlayerEvent, TimerEvent};
+
+/// Per-session sound settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Sound {
+    /// Percent, from 0 to 100.
+    pub volume: u8,
+    pub muted: bool,
+    /// Ticks once a second for this many final seconds. Zero for no ticking.
+    pub ticks: u32,
+}
+
+impl Default for Sound {
+    fn default() -> Self {
+        Self {
+            volume: 50,
+            muted: false,
+            ticks: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cue {
+    Warning,
+    Critical,
+    Expired,
+    Turn,
+    Tick,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Wave {
+    Sine,
+    Square,
+    Triangle,
+}
+
+/// A single synthesized note.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub frequency: f32,
+    /// Offset from the start of the cue, in seconds.
+    pub at: f64,
+    pub length: f64,
+    pub wave: Wave,
+}
+
+const fn tone(frequency: f32, at: f64, length: f64, wave: Wave) -> Tone {
+    Tone {
+        frequency,
+        at,
+        length,
+        wave,
+    }
+}
+
+impl Cue {
+    pub fn for_event(event: TimerEvent, ticks: u32) -> Option<Self> {
+        match event {
+            TimerEvent::Warning(Level::Critical) => Some(Cue::Critical),
+            TimerEvent::Warning(_) => Some(Cue::Warning),
+            TimerEvent::Expired => Some(Cue::Expired),
+            TimerEvent::Lapped(_) => Some(Cue::Turn),
+            TimerEvent::Second(second) if second <= ticks => Some(Cue::Tick),
+            _ => None,
+        }
+    }
+
+    pub fn tones(self) -> &'static [Tone] {
+        use Wave::*;
+        const WARNING: &[Tone] = &[tone(660.0, 0.0, 0.15, Sine), tone(660.0, 0.25, 0.15, Sine)];
+        const CRITICAL: &[Tone] = &[
+            tone(880.0, 0.0, 0.1, Square),
+            tone(880.0, 0.15, 0.1, Square),
+            tone(880.0, 0.3, 0.1, Square),
+        ];
+        const EXPIRED: &[Tone] = &[
+            tone(784.0, 0.0, 0.3, Triangle),
+            tone(523.0, 0.3, 0.3, Triangle),
+            tone(392.0, 0.6, 0.6, Triangle),
+        ];
+        const TURN: &[Tone] = &[tone(523.0, 0.0, 0.08, Sine), tone(784.0, 0.08, 0.12, Sine)];
+        const TICK: &[Tone] = &[tone(1000.0, 0.0, 0.03, Square)];
+        match self {
+            Cue::Warning => WARNING,
+            Cue::Critical => CRITICAL,
+            Cue::Expired => EXPIRED,
+            Cue::Turn => TURN,
+            Cue::Tick => TICK,
+        }
+    }
+}
+
+impl Sound {
+    /// The volume from 0 to 1, or `None` while nothing should be heard.
+    pub fn gain(&self) -> Option<f32> {
+        Some(self.volume.min(100) as f32 / 100.0).filter(|_| !self.muted && self.volume > 0)
+    }
+
+    /// Plays the cue for `event`, if it has one and the sound is on.
+    pub fn play(&self, event: &PlayerEvent) {
+        let Some(gain) = self.gain() else {
+            return;
+        };
+        if let Some(cue) = Cue::for_event(event.event, self.ticks) {
+            audio::play(cue.tones(), gain);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod audio {
+    use std::cell::RefCell;
+
+    use web_sys::{wasm_bindgen::JsValue, AudioContext, OscillatorType};
+
+    use super::{Tone, Wave};
+
+    thread_local! {
+        // browsers limit how many contexts a page may open, so one is shared
+        static CONTEXT: RefCell<Option<AudioContext>> = const { RefCell::new(None) };
+    }
+
+    pub fn play(tones: &[Tone], volume: f32) {
+        CONTEXT.with_borrow_mut(|context| {
+            if context.is_none() {
+                *context = AudioContext::new().ok();
+            }
+            if let Some(context) = context {
+                // contexts start suspended until the page has been interacted with
+                let _ = context.resume();
+                let start = context.current_time();
+                for tone in tones {
+                    let _ = schedule(context, tone, volume, start + tone.at);
+                }
+            }
+        });
+    }
+
+    fn schedule(context: &AudioContext, tone: &Tone, volume: f32, at: f64) -> Result<(), JsValue> {
+        let oscillator = context.create_oscillator()?;
+        oscillator.set_type(match tone.wave {
+            Wave::Sine => OscillatorType::Sine,
+            Wave::Square => OscillatorType::Square,
+            Wave::Triangle => OscillatorType::Triangle,
+        });
+        oscillator.frequency().set_value(tone.frequency);
+
+        // fading out avoids a click at the end of every note
+        let gain = context.create_gain()?;
+        gain.gain().set_value_at_time(volume, at)?;
+        gain.gain()
+            .exponential_ramp_to_value_at_time(0.001, at + tone.length)?;
+
+        oscillator.connect_with_audio_node(&gain)?;
+        gain.connect_with_audio_node(&context.destination())?;
+        oscillator.start_with_when(at)?;
+        oscillator.stop_with_when(at + tone.length)?;
+        Ok(())
+    }
+}
+
+/// Web Audio is only available in the browser; elsewhere cues are silent.
+#[cfg(not(target_arch = "wasm32"))]
+mod audio {
+    use super::Tone;
+
+    pub fn play(_tones: &[Tone], _volume: f32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    #[test]
+    fn test_cues() {
+        assert_eq!(
+            Cue::for_event(TimerEvent::Warning(Level::Warning), 0),
+            Some(Cue::Warning)
+        );
+        assert_eq!(
+            Cue::for_event(TimerEvent::Warning(Level::Critical), 0),
+            Some(Cue::Critical)
+        );
+        assert_eq!(Cue::for_event(TimerEvent::Expired, 0), Some(Cue::Expired));
+        assert_eq!(
+            Cue::for_event(TimerEvent::Lapped(TimeDelta::seconds(3)), 0),
+            Some(Cue::Turn)
+        );
+        assert_eq!(Cue::for_event(TimerEvent::Started, 0), None);
+
+        // ticks only in the final seconds asked for
+        assert_eq!(Cue::for_event(TimerEvent::Second(5), 0), None);
+        assert_eq!(Cue::for_event(TimerEvent::Second(5), 5), Some(Cue::Tick));
+        assert_eq!(Cue::for_event(TimerEvent::Second(6), 5), None);
+    }
+
+    #[test]
+    fn test_cues_are_distinct() {
+        let cues = [
+            Cue::Warning,
+            Cue::Critical,
+            Cue::Expired,
+            Cue::Turn,
+            Cue::Tick,
+        ];
+        for (i, a) in cues.iter().enumerate() {
+            assert!(!a.tones().is_empty());
+            for b in &cues[i + 1..] {
+                assert_ne!(a.tones(), b.tones());
+            }
+        }
+    }
+}
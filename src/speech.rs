@@ -0,0 +1,177 @@
+use chrono::TimeDelta;
+use serde::{Deserialize, Serialize};
+
+use crate::components::{Level, TimerEvent};
+
+/// Announcements read aloud on timer events. Each template may use `{player}`
+/// for whoever the event is about, `{next}` for the player whose turn it now
+/// is and `{left}` for the time remaining. An empty template says nothing.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Speech {
+    pub enabled: bool,
+    /// Percent, from 0 to 100, apart from the volume of the sound cues.
+    pub volume: u8,
+    pub muted: bool,
+    pub warning: String,
+    pub expired: String,
+    pub turn: String,
+}
+
+impl Default for Speech {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            volume: 100,
+            muted: false,
+            warning: "{player}, {left} left".to_string(),
+            expired: "Time's up".to_string(),
+            turn: "Next up: {next}".to_string(),
+        }
+    }
+}
+
+/// Who and what an announcement is about.
+pub struct Context<'a> {
+    pub player: &'a str,
+    pub next: &'a str,
+    pub left: TimeDelta,
+}
+
+impl Speech {
+    /// The text to say for `event`, if anything.
+    pub fn announcement(&self, event: TimerEvent, context: &Context) -> Option<String> {
+        let template = match event {
+            TimerEvent::Warning(Level::Warning | Level::Critical) => &self.warning,
+            TimerEvent::Expired => &self.expired,
+            TimerEvent::Lapped(_) => &self.turn,
+            _ => return None,
+        };
+        let text = template
+            .replace("{player}", context.player)
+            .replace("{next}", context.next)
+            .replace("{left}", &spoken(context.left));
+        Some(text).filter(|text| !text.trim().is_empty())
+    }
+
+    /// The volume from 0 to 1, or `None` while nothing should be said.
+    pub fn gain(&self) -> Option<f32> {
+        Some(self.volume.min(100) as f32 / 100.0)
+            .filter(|_| self.enabled && !self.muted && self.volume > 0)
+    }
+
+    /// Says the announcement for `event`, if it has one and speech is on.
+    pub fn announce(&self, event: TimerEvent, context: &Context) {
+        let Some(gain) = self.gain() else {
+            return;
+        };
+        if let Some(text) = self.announcement(event, context) {
+            voice::say(&text, gain);
+        }
+    }
+}
+
+/// A duration the way it would be said, such as "1 minute 30 seconds".
+fn spoken(delta: TimeDelta) -> String {
+    // rounded up, like the warnings themselves
+    let secs = (delta.num_milliseconds().max(0) + 999) / 1000;
+    let unit = |n: i64, name: &str| match n {
+        1 => format!("1 {name}"),
+        n => format!("{n} {name}s"),
+    };
+    let (minutes, secs) = (secs / 60, secs % 60);
+    match (minutes, secs) {
+        (0, secs) => unit(secs, "second"),
+        (minutes, 0) => unit(minutes, "minute"),
+        (minutes, secs) => format!("{} {}", unit(minutes, "minute"), unit(secs, "second")),
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod voice {
+    use web_sys::SpeechSynthesisUtterance;
+
+    pub fn say(text: &str, volume: f32) {
+        // browsers without speech synthesis leave the property undefined
+        let Some(synth) = web_sys::window()
+            .and_then(|window| window.speech_synthesis().ok())
+            .filter(|synth| !synth.is_undefined())
+        else {
+            return;
+        };
+        if let Ok(utterance) = SpeechSynthesisUtterance::new_with_text(text) {
+            utterance.set_volume(volume);
+            synth.speak(&utterance);
+        }
+    }
+}
+
+/// Speech synthesis is only available in the browser; elsewhere it is silent.
+#[cfg(not(target_arch = "wasm32"))]
+mod voice {
+    pub fn say(_text: &str, _volume: f32) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spoken() {
+        assert_eq!(spoken(TimeDelta::seconds(30)), "30 seconds");
+        assert_eq!(spoken(TimeDelta::milliseconds(500)), "1 second");
+        assert_eq!(spoken(TimeDelta::seconds(60)), "1 minute");
+        assert_eq!(spoken(TimeDelta::seconds(150)), "2 minutes 30 seconds");
+        assert_eq!(spoken(TimeDelta::seconds(-3)), "0 seconds");
+    }
+
+    #[test]
+    fn test_announcements() {
+        let speech = Speech::default();
+        let context = Context {
+            player: "Alice",
+            next: "Bob",
+            left: TimeDelta::seconds(30),
+        };
+        let say = |event| speech.announcement(event, &context);
+        assert_eq!(
+            say(TimerEvent::Warning(Level::Warning)).as_deref(),
+            Some("Alice, 30 seconds left")
+        );
+        assert_eq!(say(TimerEvent::Expired).as_deref(), Some("Time's up"));
+        assert_eq!(
+            say(TimerEvent::Lapped(TimeDelta::seconds(5))).as_deref(),
+            Some("Next up: Bob")
+        );
+        assert_eq!(say(TimerEvent::Second(3)), None);
+
+        let quiet = Speech {
+            expired: " ".to_string(),
+            ..Speech::default()
+        };
+        assert_eq!(quiet.announcement(TimerEvent::Expired, &context), None);
+    }
+
+    #[test]
+    fn test_gain() {
+        let speech = Speech {
+            enabled: true,
+            volume: 40,
+            ..Speech::default()
+        };
+        assert_eq!(speech.gain(), Some(0.4));
+        assert_eq!(Speech::default().gain(), None);
+        for quiet in [
+            Speech {
+                muted: true,
+                ..speech.clone()
+            },
+            Speech {
+                volume: 0,
+                ..speech.clone()
+            },
+        ] {
+            assert_eq!(quiet.gain(), None);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+#[cfg(target_arch = "wasm32")]
+mod web {
+    fn local() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+
+    pub fn get(key: &str) -> Option<String> {
+        local()?.get_item(key).ok()?
+    }
+
+    pub fn set(key: &str, value: &str) {
+        if let Some(storage) = local() {
+            let _ = storage.set_item(key, value);
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+pub use web::{get, set};
+
+/// Web storage is only available in the browser; elsewhere nothing is kept.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn get(_key: &str) -> Option<String> {
+    None
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn set(_key: &str, _value: &str) {}
@@ -0,0 +1,119 @@
+use dioxus::prelude::warn;
+use serde::{Deserialize, Serialize};
+
+use crate::components::TableItem;
+use crate::storage;
+
+/// Web storage key the saved checklists are kept under.
+const KEY: &str = "moment.templates";
+
+/// A named checklist to start the table from, such as a standup agenda.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Template {
+    pub name: String,
+    pub items: Vec<TableItem>,
+}
+
+/// Every saved template, in the order they were first saved.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Templates(Vec<Template>);
+
+impl Templates {
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|template| template.name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Template> {
+        self.0.iter().find(|template| template.name == name)
+    }
+
+    /// Keeps `items` as the template `name`, replacing one of the same name.
+    /// Only what the items say is kept, not how far a game got with them.
+    pub fn save_as(&mut self, name: &str, items: &[TableItem]) -> bool {
+        let name = name.trim();
+        if name.is_empty() {
+            return false;
+        }
+        let items = items
+            .iter()
+            .enumerate()
+            .map(|(id, item)| TableItem {
+                id,
+                completed: false,
+                step: None,
+                ..item.clone()
+            })
+            .collect();
+        let template = Template {
+            name: name.to_string(),
+            items,
+        };
+        match self.0.iter_mut().find(|t| t.name == name) {
+            Some(existing) => *existing = template,
+            None => self.0.push(template),
+        }
+        true
+    }
+
+    pub fn remove(&mut self, name: &str) {
+        self.0.retain(|template| template.name != name);
+    }
+
+    pub fn load() -> Self {
+        let Some(json) = storage::get(KEY) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json)
+            .inspect_err(|err| warn!("discarding saved templates: {err}"))
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        if let Ok(json) = serde_json::to_string(self) {
+            storage::set(KEY, &json);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: usize, description: &str) -> TableItem {
+        TableItem {
+            id,
+            description: description.to_string(),
+            completed: true,
+            timebox: None,
+            step: Some(3),
+        }
+    }
+
+    #[test]
+    fn test_save_as_replaces_by_name() {
+        let mut templates = Templates::default();
+        assert!(!templates.save_as("  ", &[item(0, "Dice")]));
+        assert!(templates.save_as("Setup ", &[item(4, "Dice"), item(7, "Cards")]));
+        assert!(templates.save_as("Standup", &[item(0, "Yesterday")]));
+
+        let setup = templates.get("Setup").unwrap();
+        assert_eq!(setup.items.len(), 2);
+        // saved fresh, as a checklist to start from
+        assert_eq!(setup.items[1].id, 1);
+        assert!(!setup.items[1].completed);
+        assert_eq!(setup.items[1].step, None);
+
+        templates.save_as("Setup", &[item(0, "Board")]);
+        assert_eq!(templates.names().collect::<Vec<_>>(), ["Setup", "Standup"]);
+        assert_eq!(
+            templates.get("Setup").unwrap().items[0].description,
+            "Board"
+        );
+
+        templates.remove("Setup");
+        assert_eq!(templates.names().collect::<Vec<_>>(), ["Standup"]);
+        let json = serde_json::to_string(&templates).unwrap();
+        assert_eq!(serde_json::from_str::<Templates>(&json).unwrap(), templates);
+    }
+}
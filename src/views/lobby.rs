@@ -1,35 +1,682 @@
+use crate::components::{
+    parse_markdown, to_markdown, Adjustments, Advance, Budget, Cycle, Format, Limit, Mode,
+    Overtime, Players, Roster, Rounding, Rules, Segment, Sequence, Sign, Thresholds, TimeControl,
+    MAX_DURATION,
+};
+use crate::config::Config;
+use crate::sound::Sound;
+use crate::speech::Speech;
+use crate::templates::Templates;
 use crate::Route;
+use chrono::TimeDelta;
 use dioxus::prelude::*;
 
 #[component]
 pub fn Lobby() -> Element {
-    let mut time_limit = use_signal(|| 90);
+    let mut time_limit = use_signal(|| Limit::default().to_string());
+    let mut mode = use_signal(Mode::default);
+    // hourglass mode shares out a fixed amount of time, so it needs a limit,
+    // and a cycle takes its limits from its phases
+    let mut count_up = use_signal(|| false);
+    let counting_up = count_up() && !matches!(mode(), Mode::Hourglass | Mode::Cycle);
+    let mut cycle = use_signal(Cycle::default);
+    let mut segments = use_signal(|| {
+        Sequence::default()
+            .segments
+            .into_iter()
+            .map(|segment| (segment.name, Limit(segment.duration).to_string()))
+            .collect::<Vec<_>>()
+    });
+    let mut advance = use_signal(Advance::default);
+    let mut rounds = use_signal(|| 1u32);
+    let sequence = match mode() {
+        Mode::Sequence => parse_sequence(&segments.read(), advance(), rounds()),
+        _ => Ok(Sequence::default()),
+    };
+    let limit = match counting_up || matches!(mode(), Mode::Cycle | Mode::Sequence) {
+        true => Ok(None),
+        false => time_limit
+            .read()
+            .parse::<Limit>()
+            .map(|limit| Some(limit.0)),
+    };
+    let mut control = use_signal(TimeControl::default);
+    let mut overtime = use_signal(Overtime::default);
+    // increments, refunds and overtime settle up against a bank of time, which
+    // only the chess and hourglass modes keep
+    let banked = matches!(mode(), Mode::Chess | Mode::Hourglass);
+    let time_control = match *control.read() {
+        TimeControl::Fischer(_) | TimeControl::Bronstein(_) if !banked => TimeControl::None,
+        control => control,
+    };
+    let overtime_rule = match banked {
+        true => *overtime.read(),
+        false => Overtime::None,
+    };
+    let mut format = use_signal(Format::default);
+    let mut adjustments = use_signal(|| Adjustments::default().to_string());
+    let parsed_adjustments = adjustments.read().parse::<Adjustments>();
+    let mut budget_count = use_signal(String::new);
+    let mut budget_total = use_signal(String::new);
+    let budget = parse_budget(&budget_count.read(), &budget_total.read());
+    let mut thresholds = use_signal(Thresholds::default);
+    let mut sound = use_signal(Sound::default);
+    let mut speech = use_signal(Speech::default);
+    let roster = use_context::<Signal<Roster>>();
+    let seats = match (mode(), roster.read().len()) {
+        (Mode::Hourglass, players @ 3..) => Err(format!(
+            "Hourglass is for two players, but {players} are listed"
+        )),
+        _ => Ok(()),
+    };
+    let mut templates = use_signal(Templates::load);
+    use_effect(move || templates.read().save());
+    // the chosen template, edited as a Markdown task list before it is saved
+    let mut template = use_signal(String::new);
+    let mut template_name = use_signal(String::new);
+    let mut template_text = use_signal(String::new);
+    let table = match template.read().is_empty() {
+        true => Vec::new(),
+        false => parse_markdown(&template_text.read(), 0),
+    };
 
     rsx! {
         // div { class: "w-full flex flex-col space-y-2 mt-8",
         div { class: "flex flex-col items-center justify-center space-y-4 p-8 w-full max-w-2xl mx-auto bg-gray-800 rounded-lg shadow-xl",
             h1 { class: "text-3xl font-bold text-white", "Moment Timer" }
+            if mode() == Mode::Cycle {
+                div { class: "flex flex-col space-y-2 w-full",
+                    label { class: "text-gray-300 font-semibold", "Cycle" }
+                    div { class: "grid grid-cols-4 gap-2 w-full text-sm text-gray-300",
+                        for (name , minutes , set) in [
+                            ("Work (min)", cycle.read().work.num_minutes(), set_work as fn(&mut Cycle, TimeDelta)),
+                            ("Short break", cycle.read().short_break.num_minutes(), set_short_break),
+                            ("Long break", cycle.read().long_break.num_minutes(), set_long_break),
+                        ]
+                        {
+                            label { key: "{name}", class: "flex flex-col gap-1",
+                                "{name}"
+                                input {
+                                    class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                                    r#type: "number",
+                                    min: "1",
+                                    value: "{minutes}",
+                                    oninput: move |e| {
+                                        if let Ok(val) = e.value().parse::<u32>() {
+                                            let duration = TimeDelta::minutes(val.max(1) as i64).min(MAX_DURATION);
+                                            cycle.with_mut(|c| set(c, duration));
+                                        }
+                                    },
+                                }
+                            }
+                        }
+                        label { class: "flex flex-col gap-1",
+                            "Long break every"
+                            input {
+                                class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                                r#type: "number",
+                                min: "1",
+                                value: "{cycle.read().every}",
+                                oninput: move |e| {
+                                    if let Ok(val) = e.value().parse::<u32>() {
+                                        cycle.with_mut(|c| c.every = val.max(1));
+                                    }
+                                },
+                            }
+                        }
+                    }
+                }
+            } else if mode() == Mode::Sequence {
+                div { class: "flex flex-col space-y-2 w-full",
+                    label { class: "text-gray-300 font-semibold", "Segments" }
+                    div { class: "w-full border border-gray-600 rounded p-2 bg-gray-700 space-y-1",
+                        for (index , (name , duration)) in segments.read().clone().into_iter().enumerate() {
+                            div { key: "{index}", class: "flex items-center gap-2 group",
+                                input {
+                                    class: "flex-grow bg-slate-800 text-white p-2 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                                    r#type: "text",
+                                    placeholder: "Name",
+                                    value: "{name}",
+                                    oninput: move |e| segments.write()[index].0 = e.value(),
+                                }
+                                input {
+                                    class: "w-28 bg-slate-800 text-white p-2 rounded focus:outline-none focus:ring-2 focus:ring-blue-500",
+                                    r#type: "text",
+                                    placeholder: "1m30s",
+                                    value: "{duration}",
+                                    oninput: move |e| segments.write()[index].1 = e.value(),
+                                }
+                                button {
+                                    class: "text-red-500 hover:text-red-400 font-bold px-2 invisible group-hover:visible",
+                                    onclick: move |_| {
+                                        segments.write().remove(index);
+                                    },
+                                    "✕"
+                                }
+                            }
+                        }
+                        button {
+                            class: "w-full p-2 rounded bg-slate-800 hover:bg-slate-600 text-slate-300",
+                            onclick: move |_| segments.write().push((String::new(), "1m".to_string())),
+                            "Add segment"
+                        }
+                    }
+                    div { class: "flex space-x-2 w-full",
+                        select {
+                            class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                            value: "{advance}",
+                            onchange: move |e| {
+                                if let Ok(val) = e.value().parse::<Advance>() {
+                                    advance.set(val);
+                                }
+                            },
+                            option { value: "{Advance::Auto}", "Move on when time is up" }
+                            option { value: "{Advance::Tap}", "Move on when tapped" }
+                        }
+                        input {
+                            class: "w-28 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                            r#type: "number",
+                            min: "1",
+                            max: "{Sequence::MAX_ROUNDS}",
+                            title: "Rounds",
+                            value: "{rounds}",
+                            oninput: move |e| {
+                                if let Ok(val) = e.value().parse::<u32>() {
+                                    rounds.set(val.clamp(1, Sequence::MAX_ROUNDS));
+                                }
+                            },
+                        }
+                    }
+                    if let Err(err) = &sequence {
+                        p { class: "text-sm text-red-400", "{err}" }
+                    }
+                }
+            } else {
+                div { class: "flex flex-col space-y-2 w-full",
+                    div { class: "flex items-center justify-between",
+                        label { class: "text-gray-300 font-semibold", "Time Limit" }
+                        label { class: "flex items-center gap-2 text-sm text-gray-300",
+                            input {
+                                r#type: "checkbox",
+                                class: "accent-blue-500",
+                                disabled: mode() == Mode::Hourglass,
+                                checked: counting_up,
+                                onchange: move |e| count_up.set(e.checked()),
+                            }
+                            "Count up with no limit"
+                        }
+                    }
+                    input {
+                        disabled: counting_up,
+                        class: if limit.is_ok() { "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500" } else { "w-full p-3 rounded bg-gray-700 text-white border border-red-500 focus:outline-none" },
+                        r#type: "text",
+                        placeholder: "90, 1:30, 1m30s",
+                        value: "{time_limit}",
+                        oninput: move |e| time_limit.set(e.value()),
+                    }
+                    if let Err(err) = &limit {
+                        p { class: "text-sm text-red-400", "Time limit: {err}" }
+                    }
+                }
+            }
             div { class: "flex flex-col space-y-2 w-full",
-                label { class: "text-gray-300 font-semibold", "Time Limit (seconds)" }
-                input {
+                label { class: "text-gray-300 font-semibold", "Mode" }
+                select {
                     class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
-                    r#type: "number",
-                    min: "1",
-                    value: "{time_limit}",
-                    oninput: move |e| {
-                        if let Ok(val) = e.value().parse::<u32>() {
-                            time_limit.set(val);
+                    value: "{mode}",
+                    onchange: move |e| {
+                        if let Ok(val) = e.value().parse::<Mode>() {
+                            mode.set(val);
                         }
                     },
+                    option { value: "{Mode::Turn}", "Per turn" }
+                    option { value: "{Mode::Chess}", "Chess clock (time bank per player)" }
+                    option { value: "{Mode::Hourglass}", "Hourglass (two players)" }
+                    option { value: "{Mode::Cycle}", "Pomodoro (work and breaks)" }
+                    option { value: "{Mode::Sequence}", "Sequence of named segments" }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Time Control" }
+                div { class: "flex space-x-2 w-full",
+                    select {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        value: "{time_control.name()}",
+                        onchange: move |e| {
+                            let amount = control.read().amount().num_seconds();
+                            if let Ok(val) = format!("{}:{}", e.value(), amount).parse::<TimeControl>() {
+                                control.set(val);
+                            }
+                        },
+                        option { value: "none", "None" }
+                        option { value: "fischer", disabled: !banked, "Fischer (+N seconds per move)" }
+                        option { value: "delay", "US delay (N seconds before the clock starts)" }
+                        option { value: "bronstein", disabled: !banked, "Bronstein (refund up to N seconds used)" }
+                    }
+                    input {
+                        class: "w-28 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                        r#type: "number",
+                        min: "0",
+                        disabled: time_control == TimeControl::None,
+                        value: "{control.read().amount().num_seconds()}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                let amount = TimeDelta::seconds(val as i64);
+                                control.with_mut(|c| *c = c.with_amount(amount));
+                            }
+                        },
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Overtime" }
+                div { class: "flex space-x-2 w-full",
+                    select {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                        disabled: !banked,
+                        value: "{overtime_rule.name()}",
+                        onchange: move |e| {
+                            let current = *overtime.read();
+                            let count = current.count().max(1);
+                            let period = current.period().num_seconds().max(30);
+                            if let Ok(val) = format!("{}:{}:{}", e.value(), count, period).parse::<Overtime>() {
+                                overtime.set(val);
+                            }
+                        },
+                        option { value: "none", "None" }
+                        option { value: "byoyomi", "Byo-yomi (N periods of M seconds)" }
+                        option { value: "canadian", "Canadian (N moves in M seconds)" }
+                    }
+                    input {
+                        class: "w-20 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                        r#type: "number",
+                        min: "1",
+                        title: "N",
+                        disabled: overtime_rule == Overtime::None,
+                        value: "{overtime.read().count()}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                overtime.with_mut(|o| *o = o.with_count(val.max(1)));
+                            }
+                        },
+                    }
+                    input {
+                        class: "w-24 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500 disabled:opacity-50",
+                        r#type: "number",
+                        min: "1",
+                        title: "M (seconds)",
+                        disabled: overtime_rule == Overtime::None,
+                        value: "{overtime.read().period().num_seconds()}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                let period = TimeDelta::seconds(val.max(1) as i64);
+                                overtime.with_mut(|o| *o = o.with_period(period));
+                            }
+                        },
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Time Buttons" }
+                input {
+                    class: if parsed_adjustments.is_ok() { "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500" } else { "w-full p-3 rounded bg-gray-700 text-white border border-red-500 focus:outline-none" },
+                    r#type: "text",
+                    placeholder: "-10s, +15s, +1m",
+                    value: "{adjustments}",
+                    oninput: move |e| adjustments.set(e.value()),
+                }
+                if let Err(err) = &parsed_adjustments {
+                    p { class: "text-sm text-red-400", "Time buttons: {err}" }
+                }
+                div { class: "flex space-x-2 w-full",
+                    input {
+                        class: "w-1/2 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "number",
+                        min: "0",
+                        placeholder: "Extensions per player",
+                        title: "How many times each player may add time (empty for no limit)",
+                        value: "{budget_count}",
+                        oninput: move |e| budget_count.set(e.value()),
+                    }
+                    input {
+                        class: "w-1/2 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "text",
+                        placeholder: "Extra time per player",
+                        title: "How much time each player may add in total (empty for no limit)",
+                        value: "{budget_total}",
+                        oninput: move |e| budget_total.set(e.value()),
+                    }
+                }
+                if let Err(err) = &budget {
+                    p { class: "text-sm text-red-400", "{err}" }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Display" }
+                div { class: "flex space-x-2 w-full",
+                    select {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        value: if format.read().hours { "hms" } else { "ms" },
+                        onchange: move |e| format.with_mut(|f| f.hours = e.value() == "hms"),
+                        option { value: "ms", "MM:SS" }
+                        option { value: "hms", "H:MM:SS" }
+                    }
+                    select {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        value: match format.read().sign {
+                            Sign::Hidden => "hidden",
+                            Sign::Minus => "minus",
+                            Sign::Plus => "plus",
+                        },
+                        onchange: move |e| {
+                            let sign = match e.value().as_str() {
+                                "minus" => Sign::Minus,
+                                "plus" => Sign::Plus,
+                                _ => Sign::Hidden,
+                            };
+                            format.with_mut(|f| f.sign = sign);
+                        },
+                        option { value: "hidden", "Overtime unmarked" }
+                        option { value: "minus", "Overtime as -00:05" }
+                        option { value: "plus", "Overtime as +00:05" }
+                    }
+                    select {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        value: if format.read().rounding == Rounding::Ceil { "ceil" } else { "floor" },
+                        onchange: move |e| {
+                            let rounding = if e.value() == "ceil" { Rounding::Ceil } else { Rounding::Floor };
+                            format.with_mut(|f| f.rounding = rounding);
+                        },
+                        option { value: "floor", "Round down" }
+                        option { value: "ceil", "Round up" }
+                    }
+                    input {
+                        class: "w-24 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "number",
+                        min: "0",
+                        title: "Show tenths below N seconds (0 for never)",
+                        value: "{format.read().tenths.map_or(0, |t| t.num_seconds())}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                let tenths = (val > 0).then(|| TimeDelta::seconds(val as i64));
+                                format.with_mut(|f| f.tenths = tenths);
+                            }
+                        },
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Warnings (seconds left, 0 for none)" }
+                div { class: "flex space-x-2 w-full",
+                    input {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-yellow-500 focus:outline-none focus:border-blue-500",
+                        r#type: "number",
+                        min: "0",
+                        title: "Warning",
+                        value: "{thresholds.read().warning.num_seconds()}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                thresholds.with_mut(|t| t.warning = TimeDelta::seconds(val as i64).min(MAX_DURATION));
+                            }
+                        },
+                    }
+                    input {
+                        class: "flex-grow p-3 rounded bg-gray-700 text-white border border-red-500 focus:outline-none focus:border-blue-500",
+                        r#type: "number",
+                        min: "0",
+                        title: "Critical",
+                        value: "{thresholds.read().critical.num_seconds()}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                thresholds.with_mut(|t| t.critical = TimeDelta::seconds(val as i64).min(MAX_DURATION));
+                            }
+                        },
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Sound" }
+                div { class: "flex space-x-2 w-full items-center",
+                    input {
+                        class: "flex-grow accent-blue-500",
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        title: "Volume",
+                        value: "{sound.read().volume}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u8>() {
+                                sound.with_mut(|s| s.volume = val.min(100));
+                            }
+                        },
+                    }
+                    input {
+                        class: "w-24 p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "number",
+                        min: "0",
+                        title: "Tick each of the final N seconds (0 for never)",
+                        value: "{sound.read().ticks}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u32>() {
+                                sound.with_mut(|s| s.ticks = val);
+                            }
+                        },
+                    }
+                }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "flex items-center gap-2 text-gray-300 font-semibold",
+                    input {
+                        r#type: "checkbox",
+                        class: "accent-blue-500",
+                        checked: speech.read().enabled,
+                        onchange: move |e| speech.with_mut(|s| s.enabled = e.checked()),
+                    }
+                    "Announce aloud"
+                }
+                if speech.read().enabled {
+                    input {
+                        class: "w-full accent-blue-500",
+                        r#type: "range",
+                        min: "0",
+                        max: "100",
+                        title: "Announcement volume",
+                        value: "{speech.read().volume}",
+                        oninput: move |e| {
+                            if let Ok(val) = e.value().parse::<u8>() {
+                                speech.with_mut(|s| s.volume = val.min(100));
+                            }
+                        },
+                    }
+                    p { class: "text-sm text-gray-400",
+                        "Use {{player}}, {{next}} and {{left}} in the announcements; leave one empty to skip it."
+                    }
+                    input {
+                        class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "text",
+                        title: "On a warning",
+                        value: "{speech.read().warning}",
+                        oninput: move |e| speech.with_mut(|s| s.warning = e.value()),
+                    }
+                    input {
+                        class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "text",
+                        title: "When time is up",
+                        value: "{speech.read().expired}",
+                        oninput: move |e| speech.with_mut(|s| s.expired = e.value()),
+                    }
+                    input {
+                        class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "text",
+                        title: "On the next turn",
+                        value: "{speech.read().turn}",
+                        oninput: move |e| speech.with_mut(|s| s.turn = e.value()),
+                    }
+                }
+            }
+            Players {}
+            if let Err(err) = &seats {
+                p { class: "w-full text-sm text-red-400", "{err}" }
+            }
+            div { class: "flex flex-col space-y-2 w-full",
+                label { class: "text-gray-300 font-semibold", "Table" }
+                select {
+                    class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                    value: "{template}",
+                    onchange: move |e| {
+                        let name = e.value();
+                        let items = templates.read().get(&name).map(|t| to_markdown(&t.items));
+                        template_name.set(if name == NEW_TEMPLATE { String::new() } else { name.clone() });
+                        template_text.set(items.unwrap_or_default());
+                        template.set(name);
+                    },
+                    option { value: "", "Keep the current table" }
+                    for name in templates.read().names() {
+                        option { key: "{name}", value: "{name}", "{name}" }
+                    }
+                    option { value: NEW_TEMPLATE, "New template\u{2026}" }
+                }
+                if !template.read().is_empty() {
+                    input {
+                        class: "w-full p-3 rounded bg-gray-700 text-white border border-gray-600 focus:outline-none focus:border-blue-500",
+                        r#type: "text",
+                        placeholder: "Template name",
+                        value: "{template_name}",
+                        oninput: move |e| template_name.set(e.value()),
+                    }
+                    textarea {
+                        class: "w-full h-32 p-3 rounded bg-gray-700 text-white font-mono text-sm border border-gray-600 focus:outline-none focus:border-blue-500",
+                        placeholder: "- [ ] Item\n- [ ] Timeboxed item (2m)",
+                        value: "{template_text}",
+                        oninput: move |e| template_text.set(e.value()),
+                    }
+                    div { class: "flex justify-end gap-2",
+                        if templates.read().get(&template.read()).is_some() {
+                            button {
+                                class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                                onclick: move |_| {
+                                    templates.write().remove(&template.read());
+                                    template.set(String::new());
+                                },
+                                "Delete"
+                            }
+                        }
+                        button {
+                            class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1 disabled:opacity-50",
+                            disabled: template_name.read().trim().is_empty(),
+                            onclick: move |_| {
+                                let name = template_name.read().trim().to_string();
+                                let items = parse_markdown(&template_text.read(), 0);
+                                if templates.write().save_as(&name, &items) {
+                                    template.set(name);
+                                }
+                            },
+                            "Save template"
+                        }
+                    }
                 }
             }
-            Link {
-                class: "w-full p-3 text-center bg-blue-600 hover:bg-blue-500 text-white font-bold rounded transition-colors",
-                to: Route::Play {
-                    time_limit: Some(*time_limit.read()),
-                },
-                "Start"
+            if let (Ok(limit), Ok(adjustments), Ok(budget), Ok(sequence), Ok(())) = (&limit, &parsed_adjustments, &budget, &sequence, &seats) {
+                Link {
+                    class: "w-full p-3 text-center bg-blue-600 hover:bg-blue-500 text-white font-bold rounded transition-colors",
+                    to: Route::Play {
+                        time_limit: None,
+                        config: Config {
+                            rules: Rules {
+                                mode: *mode.read(),
+                                limit: *limit,
+                                control: time_control,
+                                overtime: overtime_rule,
+                                budget: *budget,
+                                cycle: *cycle.read(),
+                                sequence: sequence.clone(),
+                            },
+                            format: *format.read(),
+                            thresholds: *thresholds.read(),
+                            adjustments: adjustments.clone(),
+                            sound: *sound.read(),
+                            speech: speech.read().clone(),
+                            players: roster.read().clone(),
+                            table: table.clone(),
+                        },
+                    },
+                    "Start"
+                }
+            } else {
+                button {
+                    class: "w-full p-3 text-center bg-blue-600 text-white font-bold rounded opacity-50 cursor-not-allowed",
+                    disabled: true,
+                    "Start"
+                }
             }
         }
     }
 }
+
+fn set_work(cycle: &mut Cycle, duration: TimeDelta) {
+    cycle.work = duration;
+}
+
+fn set_short_break(cycle: &mut Cycle, duration: TimeDelta) {
+    cycle.short_break = duration;
+}
+
+fn set_long_break(cycle: &mut Cycle, duration: TimeDelta) {
+    cycle.long_break = duration;
+}
+
+/// The value of the template choice that starts a new template, which no
+/// saved template can have because their names are trimmed.
+const NEW_TEMPLATE: &str = " new";
+
+/// Reads the segment rows, each a name and a duration.
+fn parse_sequence(
+    rows: &[(String, String)],
+    advance: Advance,
+    rounds: u32,
+) -> Result<Sequence, String> {
+    if rows.is_empty() {
+        return Err("Add at least one segment".to_string());
+    }
+    let segments = rows
+        .iter()
+        .enumerate()
+        .map(|(index, (name, duration))| {
+            let duration = duration
+                .parse::<Limit>()
+                .map_err(|err| format!("Segment {}: {err}", index + 1))?;
+            let name = match name.trim() {
+                "" => format!("Segment {}", index + 1),
+                name => name.to_string(),
+            };
+            Ok(Segment::new(&name, duration.0))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Sequence {
+        segments,
+        advance,
+        rounds,
+    })
+}
+
+/// Reads the extension budget fields, where empty means unlimited.
+fn parse_budget(count: &str, total: &str) -> Result<Budget, String> {
+    let count = match count.trim() {
+        "" => None,
+        count => Some(
+            count
+                .parse::<u32>()
+                .map_err(|_| format!("Extensions: {count:?} is not a whole number"))?,
+        ),
+    };
+    let total = match total.trim() {
+        "" => None,
+        total => Some(
+            total
+                .parse::<Limit>()
+                .map_err(|err| format!("Extra time: {err}"))?
+                .0,
+        ),
+    };
+    Ok(Budget { count, total })
+}
@@ -1,13 +1,330 @@
-use crate::components::{Table, Timer};
+use crate::components::{
+    close_agenda, new_bank, plan_agenda, sync_agenda, toggle_fullscreen, use_bank, use_shortcuts,
+    Advance, Agenda, Limit, Mode, PlayerEvent, Roster, Rules, SegmentStrip, Sequence, Shortcuts,
+    StatsPanel, Table, TableItem, Timer,
+};
+use crate::config::Config;
+use crate::keymap::{Action, Keymap};
+use crate::session::Session;
+use crate::speech::Context;
+use crate::Route;
+use chrono::{Local, TimeDelta};
 use dioxus::prelude::*;
 
 #[component]
-pub fn Play(time_limit: Option<u32>) -> Element {
+pub fn Play(time_limit: Option<Limit>, config: Config) -> Element {
+    let mut config = use_signal(move || {
+        let mut config = config;
+        // a plain `time_limit` still works, on its own or to override the config
+        if let Some(limit) = time_limit {
+            config.rules.limit = Some(limit.0);
+        }
+        config
+    });
+
+    let mut roster = use_context::<Signal<Roster>>();
+    use_effect(move || roster.set(config.peek().players.clone()));
+
+    let mut bank = use_bank(config.peek().rules.clone(), config.peek().players.len());
+    let mut items = use_context::<Signal<Vec<TableItem>>>();
+    // a template chosen in the lobby replaces the table once, on arrival, and
+    // leaves the link so that a reload keeps the table as it has become
+    use_effect(move || {
+        if !config.peek().table.is_empty() {
+            let table = std::mem::take(&mut config.write().table);
+            items.set(table);
+            navigator().replace(Route::Play {
+                time_limit,
+                config: config.peek().clone(),
+            });
+        }
+    });
+    let history = bank.read().history().clone();
+    let Config {
+        format,
+        thresholds,
+        sound,
+        ..
+    } = *config.read();
+    let (speaking, speech_muted) = {
+        let speech = &config.read().speech;
+        (speech.enabled, speech.muted)
+    };
+
+    // a meeting is the table's timeboxed items run as a sequence, ticked off
+    // as each step is passed; any other sequence leaves the table alone
+    let mut meeting = use_signal(|| false);
+    let step = use_memo(move || {
+        let current = bank.read();
+        current
+            .sequence()
+            .map(|(_, step)| current.segment().map(|_| step))
+    });
+    use_effect(move || {
+        let step = step();
+        if !meeting() {
+            return;
+        }
+        match step {
+            Some(Some(step)) => sync_agenda(&mut items.write(), step),
+            // finished, or replaced by another game
+            finished => {
+                let mut items = items.write();
+                if finished.is_some() {
+                    sync_agenda(&mut items, u32::MAX);
+                }
+                close_agenda(&mut items);
+                meeting.set(false);
+            }
+        }
+    });
+    let agenda = {
+        let current = bank.read();
+        let mut spent = history
+            .laps()
+            .iter()
+            .map(|lap| lap.duration)
+            .collect::<Vec<_>>();
+        match (current.sequence().filter(|_| meeting()), current.segment()) {
+            (Some((_, step)), Some(_)) => {
+                spent.push(current.turn_elapsed());
+                Agenda {
+                    step: Some(step),
+                    spent,
+                }
+            }
+            (Some(_), None) => Agenda { step: None, spent },
+            (None, _) => Agenda::default(),
+        }
+    };
+    let mut begin_meeting = move || {
+        let segments = plan_agenda(&mut items.write());
+        let rules = Rules {
+            mode: Mode::Sequence,
+            sequence: Sequence {
+                segments,
+                advance: Advance::Tap,
+                rounds: 1,
+            },
+            budget: config.peek().rules.budget,
+            ..Default::default()
+        };
+        bank.set(new_bank(rules.clone(), 1));
+        config.with_mut(|config| config.rules = rules);
+        bank.write().toggle();
+        meeting.set(true);
+    };
+    // starting a meeting replaces the timer, so a game under way is only
+    // given up once confirmed
+    let mut confirming = use_signal(|| false);
+    let start_meeting = move |_| match bank.peek().snapshot().started() {
+        true => confirming.set(true),
+        false => begin_meeting(),
+    };
+    let sequencing = bank.read().segment().is_some();
+
+    // a session left behind by a reload is offered before it gets overwritten
+    let mut saved = use_signal(|| Session::load().filter(|session| session.timer.started()));
+    let snapshot = use_memo(move || bank.read().snapshot());
+    use_effect(move || {
+        if saved.read().is_some() {
+            return;
+        }
+        let config = Config {
+            players: roster(),
+            ..config()
+        };
+        Session::new(snapshot(), config, items(), meeting()).save();
+    });
+
+    let mut resume = move |session: Session| {
+        if let Some(restored) = session.bank() {
+            bank.set(restored);
+            roster.set(session.config.players.clone());
+            config.set(session.config);
+            items.set(session.table);
+            meeting.set(session.meeting);
+        }
+        saved.set(None);
+    };
+
+    let mut keymap = use_signal(Keymap::load);
+    let mut help = use_signal(|| false);
+    let mut remapping = use_signal(|| Option::<Action>::None);
+    use_effect(move || keymap.read().save());
+    use_shortcuts(keymap, move |key| {
+        if let Some(action) = remapping() {
+            if key != "Escape" {
+                keymap.with_mut(|keymap| keymap.bind(action, &key));
+            }
+            remapping.set(None);
+            return;
+        }
+        let action = keymap.read().action(&key);
+        // the shortcuts sheet covers the timer, so it only listens to be closed
+        if help() {
+            if key == "Escape" || action == Some(Action::Help) {
+                help.set(false);
+            }
+            return;
+        }
+        let Some(action) = action else {
+            return;
+        };
+        // the timer reports the resulting events on its next tick
+        let adjustments = config.peek().adjustments.clone();
+        let mut adjust = |delta: Option<TimeDelta>| {
+            if let Some(Err(err)) = delta.map(|delta| bank.write().extend_time(delta)) {
+                warn!("{err}");
+            }
+        };
+        match action {
+            Action::Toggle => bank.write().toggle(),
+            Action::Next => {
+                bank.write().lap();
+            }
+            Action::Extend => adjust(adjustments.up()),
+            Action::Shorten => adjust(adjustments.down()),
+            Action::Undo => {
+                bank.write().undo();
+            }
+            Action::Redo => {
+                bank.write().redo();
+            }
+            Action::Fullscreen => toggle_fullscreen(),
+            Action::Help => help.set(true),
+        }
+    });
+
+    let saved_at = saved.read().as_ref().map(|session| {
+        session
+            .saved
+            .with_timezone(&Local)
+            .format("%H:%M")
+            .to_string()
+    });
+
     rsx! {
         div {
             class: "flex flex-col items-center justify-center space-y-8 w-full max-w-2xl mx-auto",
-            Timer { time_limit }
-            Table {}
+            if let Some(session) = saved() {
+                div { class: "flex items-center gap-2 w-full mt-8 p-3 rounded bg-slate-800 border border-slate-700",
+                    span { class: "flex-grow",
+                        "Resume the game saved at {saved_at.clone().unwrap_or_default()}?"
+                    }
+                    button {
+                        class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold",
+                        onclick: move |_| resume(session.clone()),
+                        "Resume"
+                    }
+                    button {
+                        class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                        onclick: move |_| saved.set(None),
+                        "Discard"
+                    }
+                }
+            }
+            Timer {
+                bank,
+                players: roster(),
+                format,
+                thresholds,
+                adjustments: config.read().adjustments.clone(),
+                ticks: config.read().sound.ticks,
+                on_event: move |event: PlayerEvent| {
+                    let config = config.read();
+                    config.sound.play(&event);
+                    // spoken separately, so muting the cues can leave the voice
+                    if config.speech.gain().is_some() {
+                        let bank = bank.peek();
+                        let player = roster.peek().name(event.player);
+                        // cycles and sequences hand over to their next phase or
+                        // segment rather than to a player
+                        let next = match (bank.phase(), bank.segment()) {
+                            (Some((phase, _)), _) => phase.to_string(),
+                            (_, Some(segment)) => segment.name.clone(),
+                            _ => roster.peek().name(bank.active_index()),
+                        };
+                        let context = Context {
+                            player: &player,
+                            next: &next,
+                            left: bank.face(event.player),
+                        };
+                        config.speech.announce(event.event, &context);
+                    }
+                },
+            }
+            div { class: "flex items-center gap-2 w-full text-sm text-slate-400",
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                    title: if sound.muted { "Unmute" } else { "Mute" },
+                    onclick: move |_| config.with_mut(|c| c.sound.muted = !c.sound.muted),
+                    if sound.muted {
+                        "\u{1F507}"
+                    } else {
+                        "\u{1F50A}"
+                    }
+                }
+                input {
+                    class: "flex-grow accent-blue-500",
+                    r#type: "range",
+                    min: "0",
+                    max: "100",
+                    disabled: sound.muted,
+                    value: "{sound.volume}",
+                    oninput: move |e| {
+                        if let Ok(volume) = e.value().parse::<u8>() {
+                            config.with_mut(|c| c.sound.volume = volume.min(100));
+                        }
+                    },
+                }
+                span { class: "w-10 text-right", "{sound.volume}%" }
+                if speaking {
+                    button {
+                        class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                        title: if speech_muted { "Unmute announcements" } else { "Mute announcements" },
+                        onclick: move |_| config.with_mut(|c| c.speech.muted = !c.speech.muted),
+                        if speech_muted {
+                            "\u{1F910}"
+                        } else {
+                            "\u{1F5E3}"
+                        }
+                    }
+                }
+                button {
+                    class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                    title: "Keyboard shortcuts",
+                    onclick: move |_| help.set(true),
+                    "\u{2328}"
+                }
+            }
+            SegmentStrip { bank }
+            StatsPanel { history, players: roster() }
+            if confirming() {
+                div { class: "flex items-center gap-2 w-full p-3 rounded bg-slate-800 border border-slate-700",
+                    span { class: "flex-grow", "Start the meeting and end the game under way?" }
+                    button {
+                        class: "bg-blue-600 hover:bg-blue-500 text-white rounded px-3 py-1 font-bold",
+                        onclick: move |_| {
+                            confirming.set(false);
+                            begin_meeting();
+                        },
+                        "Start meeting"
+                    }
+                    button {
+                        class: "bg-gray-700 hover:bg-gray-600 text-white rounded px-3 py-1",
+                        onclick: move |_| confirming.set(false),
+                        "Cancel"
+                    }
+                }
+            }
+            Table {
+                items,
+                agenda,
+                sequencing,
+                on_start: start_meeting,
+            }
+            Shortcuts { keymap, open: help, remapping }
         }
     }
 }